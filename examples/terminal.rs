@@ -0,0 +1,55 @@
+use asciiengine::backend::TerminalBackend;
+use asciiengine::input::{self, InputEvent, Key, TerminalInput};
+use asciiengine::screen::{CharacterColor, TextAlign, TextBufferInterface, TextBufferScreen};
+use asciiengine::ui::{self, Constraint};
+
+fn main() -> Result<(), String> {
+    let (width, height) = input::terminal_size().unwrap_or((80, 24));
+
+    let mut input = TerminalInput::new()?;
+    let mut backend = TerminalBackend::new(std::io::stdout())?;
+    let mut text_buffer = TextBufferScreen::new(width, height);
+
+    let inventory = ui::GraphicalMenu::new(vec!["I0", "Item1", "Item2", "Item3", "Item4"], (8, 0).into(),
+        ui::MenuSettings::new().horizontal(0).align(TextAlign::Center).fix_selection().hide_others().wrapping());
+
+    let mut scrollbox = ui::ScrollBox::new((0, 0, 20, 20).into(), (50, 50));
+
+    for y in 0..50 {
+        scrollbox.write_string((0, y).into(), &format!("This is line number {}, it will contain many characters so as to show how scroll menus work", y), CharacterColor::BrightWhite.into()).unwrap();
+    }
+
+    let mut layout = ui::ScreenLayout::new(ui::Layout::horizontal()
+        .pane(Constraint::Percent(50), "left")
+        .pane(Constraint::Fill(1), "right"));
+    layout.update(&text_buffer);
+
+    let mut widgets = ui::FocusManager::new();
+    let scroll_index = widgets.push_pane(scrollbox, layout["left"], Some(("Scroll", TextAlign::Center)));
+    let inventory_index = widgets.push_pane(inventory, layout["right"], Some(("Inventory", TextAlign::Center)));
+
+    let mut running = true;
+
+    while running {
+        for event in input.poll_events() {
+            match event {
+                InputEvent::Quit | InputEvent::KeyDown { key: Key::Escape, .. } => { running = false; }
+                InputEvent::Resize(width, height) => text_buffer.resize_buffer(width, height),
+                event => { widgets.handle_event(&event); }
+            }
+        }
+
+        if layout.update(&text_buffer) {
+            text_buffer.clear_rect(text_buffer.screen_rect());
+            widgets.set_pane_rect(scroll_index, layout["left"]);
+            widgets.set_pane_rect(inventory_index, layout["right"]);
+        }
+
+        widgets.draw(&mut text_buffer);
+        text_buffer.render(&mut backend)?;
+
+        std::thread::sleep(std::time::Duration::from_millis(16));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use std::time::{Duration, Instant};
+
+use crate::input::InputEvent;
+use crate::scaling::ScaleMode;
+use crate::screen::TextBufferScreen;
+
+/// Hooks for a game or tool driven by a game loop. Events are delivered as they arrive, update runs at a fixed rate
+/// and render is called after any frame with events or updates
+pub trait App {
+    /// Handle an input event, the loop stops after a quit event has been handled
+    fn handle_event(&mut self, _event: &InputEvent) {}
+
+    /// Advance the simulation by one tick of the given length
+    fn update(&mut self, dt: Duration);
+
+    /// Draw to the screen, only cells which change are presented
+    fn render(&mut self, screen: &mut TextBufferScreen);
+
+    /// Checked after every frame, the loop stops once this is false
+    fn is_running(&self) -> bool {
+        true
+    }
+
+    /// How the screen is fitted to the window, read every frame so apps can zoom or change modes. None keeps the current mode
+    fn scale_mode(&self) -> Option<ScaleMode> {
+        None
+    }
+
+    /// Whether the window should be fullscreen, read every frame. None leaves the window as it is
+    fn fullscreen(&self) -> Option<bool> {
+        None
+    }
+}
+
+/// How often frames are presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameLimit {
+    /// Present as often as the screen changes
+    Uncapped,
+    /// Present at most this many times a second
+    Fps(u32),
+    /// Let presenting wait for the display, the canvas must be created with vsync for this to limit anything
+    VSync
+}
+
+/// Settings for a game loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopSettings {
+    pub tick_rate: u32,
+    pub frame_limit: FrameLimit,
+    /// Updates run in a single frame before the loop falls behind instead of trying to catch up
+    pub max_ticks_per_frame: usize
+}
+
+impl LoopSettings {
+    pub const fn new() -> Self {
+        Self {
+            tick_rate: 60,
+            frame_limit: FrameLimit::Fps(60),
+            max_ticks_per_frame: 5
+        }
+    }
+
+    /// Set how many times update is called a second
+    pub const fn tick_rate(mut self, rate: u32) -> Self {
+        self.tick_rate = rate;
+        self
+    }
+
+    pub const fn fps(mut self, fps: u32) -> Self {
+        self.frame_limit = FrameLimit::Fps(fps);
+        self
+    }
+
+    pub const fn vsync(mut self) -> Self {
+        self.frame_limit = FrameLimit::VSync;
+        self
+    }
+
+    pub const fn uncapped(mut self) -> Self {
+        self.frame_limit = FrameLimit::Uncapped;
+        self
+    }
+
+    pub const fn max_ticks_per_frame(mut self, ticks: usize) -> Self {
+        self.max_ticks_per_frame = ticks;
+        self
+    }
+}
+
+impl std::default::Default for LoopSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits elapsed time into fixed length ticks, carrying the remainder over to the next frame
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    step: Duration,
+    accumulator: Duration,
+    max_steps: usize
+}
+
+impl FixedTimestep {
+    /// Construct a timestep running at the given number of ticks a second, running at most max_steps ticks for each call to advance
+    pub fn new(tick_rate: u32, max_steps: usize) -> Self {
+        Self {
+            step: Duration::from_secs(1) / tick_rate.max(1),
+            accumulator: Duration::ZERO,
+            max_steps: max_steps.max(1)
+        }
+    }
+
+    /// Get the length of a tick
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// Add elapsed time and get the number of ticks which are due. Time past the limit on ticks is dropped so a long stall does not cause a burst of updates
+    pub fn advance(&mut self, elapsed: Duration) -> usize {
+        self.accumulator += elapsed;
+
+        let ticks = (self.accumulator.as_nanos() / self.step.as_nanos()) as usize;
+        if ticks > self.max_steps {
+            self.accumulator = Duration::ZERO;
+            self.max_steps
+        }
+        else {
+            self.accumulator -= self.step * ticks as u32;
+            ticks
+        }
+    }
+
+    /// Get how far through the next tick the carried over time is, from 0 to 1, for interpolating between ticks when drawing
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.step.as_secs_f32()
+    }
+
+    /// Get the time left until the next tick is due
+    pub fn until_next(&self) -> Duration {
+        self.step.saturating_sub(self.accumulator)
+    }
+}
+
+/// What happened during a frame of a game loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    /// Number of updates which ran
+    pub ticks: usize,
+    /// The screen changed and should be presented
+    pub present: bool,
+    /// When the next frame should start, the caller should wait until then, waking early for input
+    pub next_frame: Instant
+}
+
+/// A fixed timestep game loop which does not own the display, so it can be driven by any window or terminal.
+/// Each call to frame delivers events, runs the ticks which are due and renders, then says when the next frame should start.
+/// Frames after the first where nothing happened are skipped until the next tick so an idle app does not spin the CPU
+#[derive(Debug, Clone)]
+pub struct GameLoop {
+    settings: LoopSettings,
+    timestep: FixedTimestep,
+    last_frame: Option<Instant>,
+    running: bool
+}
+
+impl GameLoop {
+    pub fn new(settings: LoopSettings) -> Self {
+        Self {
+            settings,
+            timestep: FixedTimestep::new(settings.tick_rate, settings.max_ticks_per_frame),
+            last_frame: None,
+            running: true
+        }
+    }
+
+    pub fn settings(&self) -> &LoopSettings {
+        &self.settings
+    }
+
+    pub fn timestep(&self) -> &FixedTimestep {
+        &self.timestep
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    /// Run one frame starting at the given time
+    pub fn frame(&mut self, app: &mut (impl App + ?Sized), screen: &mut TextBufferScreen, events: &[InputEvent], now: Instant) -> Frame {
+        for event in events {
+            app.handle_event(event);
+            if *event == InputEvent::Quit {
+                self.running = false;
+            }
+        }
+
+        let first_frame = self.last_frame.is_none();
+        let elapsed = self.last_frame.map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        self.last_frame = Some(now);
+
+        let ticks = self.timestep.advance(elapsed);
+        for _ in 0..ticks {
+            app.update(self.timestep.step());
+        }
+
+        // The first frame always renders so the app is shown before its first tick
+        if first_frame || ticks > 0 || !events.is_empty() {
+            app.render(screen);
+        }
+        self.running &= app.is_running();
+
+        let present = screen.is_dirty();
+        let frame_time = match self.settings.frame_limit {
+            FrameLimit::Fps(fps) if present => Duration::from_secs(1) / fps.max(1),
+            _ => Duration::ZERO
+        };
+        let wait = if present { frame_time } else { frame_time.max(self.timestep.until_next()) };
+
+        Frame { ticks, present, next_frame: now + wait }
+    }
+}
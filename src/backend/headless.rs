@@ -0,0 +1,79 @@
+use crate::screen::{Cell, TextBufferPos};
+use crate::snapshot;
+
+use super::{RenderBackend, RenderContext};
+
+/// Render backend which keeps the final grid of cells in memory instead of displaying it, used for tests and tooling
+#[derive(Debug, Clone, Default)]
+pub struct HeadlessBackend {
+    size: (usize, usize),
+    cells: Vec<Cell>,
+    frames: usize
+}
+
+impl HeadlessBackend {
+    /// Construct a new, empty headless backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the size of the last frame in cells
+    pub const fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// Get the number of frames which have been rendered
+    pub const fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Get the rendered cells in row major order
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// Get the rendered cell at the given position
+    pub fn cell(&self, pos: TextBufferPos) -> Option<&Cell> {
+        if 0 <= pos.x && pos.x < self.size.0 as isize && 0 <= pos.y && pos.y < self.size.1 as isize {
+            self.cells.get(pos.x as usize + pos.y as usize * self.size.0)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Dump the rendered grid as plain text
+    pub fn to_text(&self) -> String {
+        snapshot::text_layer(self.size, |x, y| self.cells[x + y * self.size.0])
+    }
+
+    /// Dump the rendered grid as plain text followed by its color and attribute layers
+    pub fn to_text_with_colors(&self) -> String {
+        snapshot::text_and_color_layers(self.size, |x, y| self.cells[x + y * self.size.0])
+    }
+}
+
+impl RenderBackend for HeadlessBackend {
+    fn begin_frame(&mut self, size: (usize, usize), clear: bool, _context: &RenderContext) -> Result<(), String> {
+        if size != self.size || clear {
+            self.size = size;
+            self.cells = vec![Cell::blank(); size.0 * size.1];
+        }
+
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, pos: TextBufferPos, cell: &Cell, _context: &RenderContext) -> Result<(), String> {
+        if pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < self.size.0 && (pos.y as usize) < self.size.1 {
+            self.cells[pos.x as usize + pos.y as usize * self.size.0] = *cell;
+        }
+
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> Result<(), String> {
+        self.frames += 1;
+
+        Ok(())
+    }
+}
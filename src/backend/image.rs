@@ -0,0 +1,89 @@
+use crate::raster::{RgbaImage, SoftwareFont};
+use crate::screen::{Cell, CellAttributes, CharacterColor, TextBufferPos};
+
+use super::{RenderBackend, RenderContext};
+
+/// Render backend drawing glyphs from a software font into an in memory image, used for screenshots without a window
+pub struct ImageBackend<'a> {
+    font: &'a SoftwareFont,
+    pixel_scale: usize,
+    image: RgbaImage
+}
+
+impl<'a> ImageBackend<'a> {
+    /// Construct a new image backend, pixel scale is a percentage of the font's character size
+    pub fn new(font: &'a SoftwareFont, pixel_scale: usize) -> Self {
+        Self {
+            font,
+            pixel_scale,
+            image: RgbaImage::new(0, 0, (0, 0, 0, 255))
+        }
+    }
+
+    /// Get the image drawn so far
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    /// Take the image drawn so far, leaving the backend to start again on the next frame
+    pub fn into_image(self) -> RgbaImage {
+        self.image
+    }
+}
+
+impl<'a> RenderBackend for ImageBackend<'a> {
+    fn begin_frame(&mut self, size: (usize, usize), clear: bool, context: &RenderContext) -> Result<(), String> {
+        let pixels = self.font.layout().grid_size(size.0, size.1, self.pixel_scale);
+
+        if clear || pixels != (self.image.width(), self.image.height()) {
+            let (r, g, b) = context.palette.get(CharacterColor::Black);
+            self.image = RgbaImage::new(pixels.0, pixels.1, (r, g, b, 255));
+        }
+
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, pos: TextBufferPos, cell: &Cell, context: &RenderContext) -> Result<(), String> {
+        let (x, y, width, height) = self.font.layout().get_dest_rect(pos.x, pos.y, self.pixel_scale);
+        let (glyph_width, glyph_height) = self.font.layout().character_size();
+
+        let (foreground, background) = cell.style.display_colors(context.palette);
+        let (r, g, b) = foreground;
+
+        self.image.fill_rect((x, y, width, height), (background.0, background.1, background.2, 255));
+
+        // Cells scaled down to nothing have no room for a glyph or lines
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        if cell.style.glyph_visible(context.blink_visible) {
+            // Glyph pixels are tinted the same way as the sdl texture color mod
+            for dy in 0..height {
+                for dx in 0..width {
+                    if let Some((gr, gg, gb)) = self.font.glyph_pixel(cell.character.0, dx * glyph_width / width, dy * glyph_height / height) {
+                        let tint = |c: u8, t: u8| (c as u16 * t as u16 / 255) as u8;
+                        self.image.set_pixel(x + dx as isize, y + dy as isize, (tint(gr, r), tint(gg, g), tint(gb, b), 255));
+                    }
+                }
+            }
+
+            // Lines are drawn one sixteenth of the cell height thick, matching a single pixel on a 9x16 font
+            let thickness = (height / 16).max(1);
+
+            if cell.style.attributes.contains(CellAttributes::UNDERLINE) {
+                self.image.fill_rect((x, y + (height - thickness) as isize, width, thickness), (r, g, b, 255));
+            }
+
+            if cell.style.attributes.contains(CellAttributes::STRIKETHROUGH) {
+                self.image.fill_rect((x, y + (height / 2) as isize, width, thickness), (r, g, b, 255));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::screen::{Cell, Palette, TextBufferPos};
+
+#[cfg(feature = "sdl")]
+pub mod sdl;
+#[cfg(feature = "sdl")]
+pub use sdl::*;
+
+pub mod headless;
+pub use headless::*;
+
+pub mod image;
+pub use image::*;
+
+pub mod terminal;
+pub use terminal::*;
+
+/// State shared with a render backend for every cell drawn in a frame
+#[derive(Debug, Clone, Copy)]
+pub struct RenderContext<'a> {
+    pub palette: &'a Palette,
+    pub blink_visible: bool
+}
+
+/// Trait for targets which a text buffer screen can be rendered to
+pub trait RenderBackend {
+    /// Prepare to draw a frame of the given size in cells, if clear is set the entire display should be cleared and only non blank cells will be drawn
+    fn begin_frame(&mut self, size: (usize, usize), clear: bool, context: &RenderContext) -> Result<(), String>;
+
+    /// Draw a single cell from a dirty region of the text buffer
+    fn draw_cell(&mut self, pos: TextBufferPos, cell: &Cell, context: &RenderContext) -> Result<(), String>;
+
+    /// Finish drawing the frame and present it
+    fn end_frame(&mut self) -> Result<(), String>;
+}
@@ -0,0 +1,97 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::character_map::CharacterMap;
+use crate::scaling::Viewport;
+use crate::screen::{Cell, CellAttributes, CharacterColor, TextBufferPos};
+
+use super::{RenderBackend, RenderContext};
+
+/// Render backend drawing glyphs from a character map onto an sdl2 canvas
+pub struct SdlBackend<'a, 'b, T: RenderTarget> {
+    canvas: &'b mut Canvas<T>,
+    character_map: &'b mut CharacterMap<'a>,
+    pixel_scale: usize,
+    origin: (i32, i32),
+    last_color: (u8, u8, u8)
+}
+
+impl<'a, 'b, T: RenderTarget> SdlBackend<'a, 'b, T> {
+    /// Construct a new sdl backend, pixel scale is a percentage of the character map's character size
+    pub fn new(canvas: &'b mut Canvas<T>, character_map: &'b mut CharacterMap<'a>, pixel_scale: usize) -> Self {
+        Self {
+            canvas,
+            character_map,
+            pixel_scale,
+            origin: (0, 0),
+            last_color: (255, 255, 255)
+        }
+    }
+
+    /// Construct a new sdl backend drawing to a viewport from a scale mode
+    pub fn with_viewport(canvas: &'b mut Canvas<T>, character_map: &'b mut CharacterMap<'a>, viewport: &Viewport) -> Self {
+        let mut backend = Self::new(canvas, character_map, viewport.pixel_scale);
+        backend.origin = viewport.origin;
+        backend
+    }
+}
+
+impl<'a, 'b, T: RenderTarget> RenderBackend for SdlBackend<'a, 'b, T> {
+    fn begin_frame(&mut self, _size: (usize, usize), clear: bool, context: &RenderContext) -> Result<(), String> {
+        self.last_color = (255, 255, 255);
+        self.character_map.texture.set_color_mod(self.last_color.0, self.last_color.1, self.last_color.2);
+
+        if clear {
+            let (r, g, b) = context.palette.get(CharacterColor::Black);
+            self.canvas.set_draw_color(Color::RGB(r, g, b));
+            self.canvas.clear();
+        }
+
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, pos: TextBufferPos, cell: &Cell, context: &RenderContext) -> Result<(), String> {
+        let source_rect = self.character_map.get_rect(cell.character.0);
+        let mut dest_rect = self.character_map.get_dest_rect(pos.x, pos.y, self.pixel_scale);
+        dest_rect.offset(self.origin.0, self.origin.1);
+
+        let (foreground, background) = cell.style.display_colors(context.palette);
+
+        // Fill in the background before the glyph is drawn over it
+        self.canvas.set_draw_color(Color::RGB(background.0, background.1, background.2));
+        self.canvas.fill_rect(dest_rect)?;
+
+        if cell.style.glyph_visible(context.blink_visible) {
+            let (r, g, b) = foreground;
+            if (r, g, b) != self.last_color {
+                self.character_map.texture.set_color_mod(r, g, b);
+                self.last_color = (r, g, b);
+            }
+
+            self.canvas.copy(&self.character_map.texture,
+                Some(source_rect),
+                Some(dest_rect)).map_err(|e| e.to_string())?;
+
+            // Lines are drawn one sixteenth of the cell height thick, matching a single pixel on a 9x16 font
+            let thickness = (dest_rect.height() / 16).max(1);
+            self.canvas.set_draw_color(Color::RGB(r, g, b));
+
+            if cell.style.attributes.contains(CellAttributes::UNDERLINE) {
+                self.canvas.fill_rect(Rect::new(dest_rect.x(), dest_rect.bottom() - thickness as i32, dest_rect.width(), thickness))?;
+            }
+
+            if cell.style.attributes.contains(CellAttributes::STRIKETHROUGH) {
+                self.canvas.fill_rect(Rect::new(dest_rect.x(), dest_rect.y() + (dest_rect.height() / 2) as i32, dest_rect.width(), thickness))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> Result<(), String> {
+        self.canvas.present();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt::Write as _;
+use std::io::Write;
+
+use crate::screen::{Cell, CellAttributes, CellStyle, Color, Palette, TextBufferPos};
+
+use super::{RenderBackend, RenderContext};
+
+/// Render backend writing ANSI escape sequences to a VT100/xterm compatible terminal, only cells which changed since the last frame are sent
+pub struct TerminalBackend<W: Write> {
+    output: W,
+    size: (usize, usize),
+    shown: Vec<Option<Cell>>,
+    frame: Vec<Cell>,
+    truecolor: bool,
+    palette: Palette
+}
+
+impl<W: Write> TerminalBackend<W> {
+    /// Construct a new terminal backend, switching the terminal to the alternate screen and hiding the cursor
+    pub fn new(mut output: W) -> Result<Self, String> {
+        output.write_all(b"\x1b[?1049h\x1b[?25l\x1b[2J").map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            output,
+            size: (0, 0),
+            shown: Vec::new(),
+            frame: Vec::new(),
+            truecolor: false,
+            palette: Palette::default()
+        })
+    }
+
+    /// Resolve the named colors with the screen's palette and send them as 24-bit colors, instead of using the terminal's own 16 color palette
+    pub fn truecolor(mut self) -> Self {
+        self.truecolor = true;
+        self
+    }
+
+    /// Forget what the terminal is showing so the next frame is sent in full
+    pub fn invalidate(&mut self) {
+        self.shown.iter_mut().for_each(|c| *c = None);
+    }
+
+    /// Append the SGR parameters selecting a color to the sequence
+    fn push_color(&self, sequence: &mut String, color: &Color, background: bool) {
+        match color {
+            Color::Named(c) if !self.truecolor => {
+                let base = if c.index() < 8 { 30 } else { 90 };
+                let offset = if background { 10 } else { 0 };
+                let _ = write!(sequence, ";{}", base + offset + c.ansi_index());
+            }
+            _ => {
+                let (r, g, b) = color.resolve(&self.palette);
+                let _ = write!(sequence, ";{};2;{};{};{}", if background { 48 } else { 38 }, r, g, b);
+            }
+        }
+    }
+
+    /// Build the escape sequence selecting a cell style, starting from a reset
+    fn style_sequence(&self, style: &CellStyle) -> String {
+        let mut sequence = String::from("\x1b[0");
+
+        for (attribute, code) in [(CellAttributes::DIM, 2), (CellAttributes::UNDERLINE, 4), (CellAttributes::BLINK, 5), (CellAttributes::REVERSE, 7), (CellAttributes::STRIKETHROUGH, 9)] {
+            if style.attributes.contains(attribute) {
+                let _ = write!(sequence, ";{}", code);
+            }
+        }
+
+        self.push_color(&mut sequence, &style.foreground, false);
+        self.push_color(&mut sequence, &style.background, true);
+        sequence.push('m');
+
+        sequence
+    }
+}
+
+impl<W: Write> RenderBackend for TerminalBackend<W> {
+    fn begin_frame(&mut self, size: (usize, usize), clear: bool, context: &RenderContext) -> Result<(), String> {
+        self.palette = context.palette.clone();
+
+        if size != self.size {
+            self.size = size;
+            self.shown = vec![None; size.0 * size.1];
+            self.frame = vec![Cell::blank(); size.0 * size.1];
+            self.output.write_all(b"\x1b[0m\x1b[2J").map_err(|e| e.to_string())?;
+        }
+        else if clear {
+            self.frame.iter_mut().for_each(|c| *c = Cell::blank());
+        }
+
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, pos: TextBufferPos, cell: &Cell, _context: &RenderContext) -> Result<(), String> {
+        if pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < self.size.0 && (pos.y as usize) < self.size.1 {
+            self.frame[pos.x as usize + pos.y as usize * self.size.0] = *cell;
+        }
+
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> Result<(), String> {
+        let mut sequence = String::new();
+        let mut cursor: Option<(usize, usize)> = None;
+        let mut style: Option<CellStyle> = None;
+
+        for y in 0..self.size.1 {
+            for x in 0..self.size.0 {
+                let i = x + y * self.size.0;
+                let cell = self.frame[i];
+
+                if self.shown[i] == Some(cell) {
+                    continue;
+                }
+
+                if cursor != Some((x, y)) {
+                    let _ = write!(sequence, "\x1b[{};{}H", y + 1, x + 1);
+                }
+
+                if style != Some(cell.style) {
+                    sequence += &self.style_sequence(&cell.style);
+                    style = Some(cell.style);
+                }
+
+                sequence.push(match cell.character.to_char() {
+                    '\0' => ' ',
+                    c => c
+                });
+
+                self.shown[i] = Some(cell);
+                cursor = Some((x + 1, y));
+            }
+        }
+
+        if !sequence.is_empty() {
+            sequence += "\x1b[0m";
+            self.output.write_all(sequence.as_bytes()).map_err(|e| e.to_string())?;
+        }
+
+        self.output.flush().map_err(|e| e.to_string())
+    }
+}
+
+impl<W: Write> Drop for TerminalBackend<W> {
+    fn drop(&mut self) {
+        let _ = self.output.write_all(b"\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = self.output.flush();
+    }
+}
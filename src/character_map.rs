@@ -1,28 +1,87 @@
+#[cfg(feature = "sdl")]
 use std::path::PathBuf;
 
+#[cfg(feature = "sdl")]
 use sdl2::{render::{Texture, TextureCreator}, rect::Rect};
 
-pub struct CharacterMap<'a> {
-    pub texture: Texture<'a>,
+/// Geometry of the glyphs in a codepage bitmap, shared by the window and software renderers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlyphLayout {
     character_size: (usize, usize),
     start_pos: (usize, usize),
     per_row: usize
 }
 
+impl GlyphLayout {
+    /// Construct a new glyph layout
+    pub const fn new(character_size: (usize, usize), start_pos: (usize, usize), per_row: usize) -> Self {
+        Self {
+            character_size,
+            start_pos,
+            per_row
+        }
+    }
+
+    /// Get the size of a character for the map
+    pub const fn character_size(&self) -> (usize, usize) {
+        self.character_size
+    }
+
+    /// Get the rectangle for a character as (x, y, width, height) in pixels
+    pub const fn get_rect(&self, character_index: u8) -> (isize, isize, usize, usize) {
+        let rows = 256 / self.per_row;
+
+        (self.start_pos.0 as isize + self.character_size.0 as isize * (character_index as usize % self.per_row) as isize,
+         self.start_pos.1 as isize + self.character_size.1 as isize * ((character_index as usize / self.per_row) % rows) as isize,
+         self.character_size.0,
+         self.character_size.1)
+    }
+
+    /// Get the destination rectangle for a character as (x, y, width, height) in pixels.
+    /// Each cell ends where the next one starts, so at fractional scales cells differ in size by at most a pixel instead of leaving gaps
+    pub const fn get_dest_rect(&self, x: isize, y: isize, pixel_scale: usize) -> (isize, isize, usize, usize) {
+        let (left, top) = self.cell_origin(x, y, pixel_scale);
+        let (right, bottom) = self.cell_origin(x + 1, y + 1, pixel_scale);
+
+        (left, top, (right - left) as usize, (bottom - top) as usize)
+    }
+
+    /// Get the top left corner of a cell in pixels
+    const fn cell_origin(&self, x: isize, y: isize, pixel_scale: usize) -> (isize, isize) {
+        ((x * self.character_size.0 as isize * pixel_scale as isize).div_euclid(100),
+         (y * self.character_size.1 as isize * pixel_scale as isize).div_euclid(100))
+    }
+
+    /// Get the size in pixels of a grid of cells
+    pub const fn grid_size(&self, columns: usize, rows: usize, pixel_scale: usize) -> (usize, usize) {
+        let (width, height) = self.cell_origin(columns as isize, rows as isize, pixel_scale);
+        (width as usize, height as usize)
+    }
+}
+
+#[cfg(feature = "sdl")]
+pub struct CharacterMap<'a> {
+    pub texture: Texture<'a>,
+    layout: GlyphLayout
+}
+
+#[cfg(feature = "sdl")]
 impl<'a> CharacterMap<'a> {
     /// Construct a new character map from a bitmap texture
     pub fn new(texture: Texture<'a>, character_size: (usize, usize), start_pos: (usize, usize), per_row: usize) -> Self {
         Self {
             texture,
-            character_size,
-            start_pos,
-            per_row
+            layout: GlyphLayout::new(character_size, start_pos, per_row)
         }
     }
 
     /// Construct a new character map from a filename
     pub fn from_file<T: 'a>(path: impl Into<PathBuf>, texture_creator: &'a TextureCreator<T>, character_size: (usize, usize), start_pos: (usize, usize), per_row: usize) -> Result<Self, String> {
-        let temp_surface = sdl2::surface::Surface::load_bmp(path.into())?;
+        let mut temp_surface = sdl2::surface::Surface::load_bmp(path.into())?;
+
+        // Make the space around the glyphs transparent so cell backgrounds show through
+        temp_surface.set_color_key(true, sdl2::pixels::Color::RGB(0, 0, 0))?;
+
         let texture = texture_creator
             .create_texture_from_surface(&temp_surface)
             .map_err(|e| e.to_string())?;
@@ -30,26 +89,25 @@ impl<'a> CharacterMap<'a> {
         Ok(Self::new(texture, character_size, start_pos, per_row))
     }
 
+    /// Get the layout of the glyphs in the map
+    pub const fn layout(&self) -> &GlyphLayout {
+        &self.layout
+    }
+
     /// Get the size of a character for the map
     pub const fn character_size(&self) -> (usize, usize) {
-        self.character_size
+        self.layout.character_size()
     }
 
     /// Get the rectangle for a character
     pub fn get_rect(&self, character_index: u8) -> Rect {
-        let rows = 256 / self.per_row;
-
-        Rect::new(self.start_pos.0 as i32 + self.character_size.0 as i32 * (character_index as usize % self.per_row) as i32,
-                  self.start_pos.1 as i32 + self.character_size.1 as i32 * ((character_index as usize / self.per_row) % rows) as i32,
-              self.character_size.0 as u32,
-             self.character_size.1 as u32)
+        let (x, y, width, height) = self.layout.get_rect(character_index);
+        Rect::new(x as i32, y as i32, width as u32, height as u32)
     }
 
     /// Get the destination rectangle for a character
     pub fn get_dest_rect(&self, x: isize, y: isize, pixel_scale: usize) -> Rect {
-        Rect::new((x * self.character_size.0 as isize * pixel_scale as isize / 100) as i32,
-            (y * self.character_size.1 as isize * pixel_scale as isize / 100) as i32,
-            (self.character_size.0 * pixel_scale / 100) as u32, 
-            (self.character_size.1 * pixel_scale / 100) as u32)
+        let (x, y, width, height) = self.layout.get_dest_rect(x, y, pixel_scale);
+        Rect::new(x as i32, y as i32, width as u32, height as u32)
     }
 }
\ No newline at end of file
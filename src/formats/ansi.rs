@@ -0,0 +1,482 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::screen::{Cell, CellAttributes, CellStyle, CharacterColor, Color, Palette, TextBufferInterface, TextBufferPos, TextBufferScreen, VGAChar};
+
+/// Width used for ANSI art without a SAUCE record, which is the width of a DOS console
+pub const ANSI_DEFAULT_WIDTH: usize = 80;
+
+/// Cursor movement and SAUCE heights past this many rows are clamped, so a corrupt file can not allocate an enormous screen
+const ANSI_MAX_ROWS: usize = 10000;
+
+/// SAUCE widths past this many columns are clamped for the same reason
+const ANSI_MAX_COLUMNS: usize = 1000;
+
+/// SAUCE flag set when blink should be shown as a bright background instead, known as iCE colors
+pub const SAUCE_ICE_COLORS: u8 = 1;
+
+/// Length of the SAUCE record at the end of a file
+const SAUCE_LENGTH: usize = 128;
+
+/// Length of each line of a SAUCE comment block
+const SAUCE_COMMENT_LENGTH: usize = 64;
+
+/// Metadata record appended to the end of ANSI art files
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Sauce {
+    pub title: String,
+    pub author: String,
+    pub group: String,
+    /// Creation date in the form CCYYMMDD
+    pub date: String,
+    pub data_type: u8,
+    pub file_type: u8,
+    /// Width of the art in characters, zero if unknown
+    pub width: u16,
+    /// Height of the art in lines, zero if unknown
+    pub height: u16,
+    pub flags: u8,
+    /// Name of the font the art was drawn with
+    pub font: String,
+    pub comments: Vec<String>
+}
+
+impl Sauce {
+    /// Construct a new record describing character based ANSI art of the given size
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            data_type: 1,
+            file_type: 1,
+            width: width.min(u16::MAX as usize) as u16,
+            height: height.min(u16::MAX as usize) as u16,
+            ..Default::default()
+        }
+    }
+
+    /// Check if the art uses iCE colors
+    pub const fn ice_colors(&self) -> bool {
+        self.flags & SAUCE_ICE_COLORS != 0
+    }
+
+    /// Find the SAUCE record at the end of a file, returning it and the length of the content before it
+    pub fn parse(bytes: &[u8]) -> Option<(Self, usize)> {
+        let start = bytes.len().checked_sub(SAUCE_LENGTH)?;
+        let record = &bytes[start..];
+
+        if &record[..7] != b"SAUCE00" {
+            return None;
+        }
+
+        let u16_at = |i: usize| u16::from_le_bytes([record[i], record[i + 1]]);
+
+        let mut sauce = Self {
+            title: cp437_field(&record[7..42]),
+            author: cp437_field(&record[42..62]),
+            group: cp437_field(&record[62..82]),
+            date: cp437_field(&record[82..90]),
+            data_type: record[94],
+            file_type: record[95],
+            width: u16_at(96),
+            height: u16_at(98),
+            flags: record[105],
+            font: cp437_field(&record[106..128]),
+            comments: Vec::new()
+        };
+
+        let mut content_end = start;
+
+        // Comments are stored in a block just before the record
+        let comment_count = record[104] as usize;
+        if comment_count > 0 {
+            let block = 5 + comment_count * SAUCE_COMMENT_LENGTH;
+            if let Some(block_start) = start.checked_sub(block) {
+                if &bytes[block_start..block_start + 5] == b"COMNT" {
+                    sauce.comments = bytes[block_start + 5..start].chunks(SAUCE_COMMENT_LENGTH).map(cp437_field).collect();
+                    content_end = block_start;
+                }
+            }
+        }
+
+        // The end of file character written before the metadata is not part of the art
+        if content_end > 0 && bytes[content_end - 1] == 0x1A {
+            content_end -= 1;
+        }
+
+        Some((sauce, content_end))
+    }
+
+    /// Encode the record along with its end of file marker and comment block, the file size is the length of the content it follows
+    pub fn encode(&self, file_size: usize) -> Vec<u8> {
+        let mut result = vec![0x1A];
+
+        if !self.comments.is_empty() {
+            result.extend_from_slice(b"COMNT");
+            for comment in self.comments.iter().take(255) {
+                push_cp437_field(&mut result, comment, SAUCE_COMMENT_LENGTH);
+            }
+        }
+
+        result.extend_from_slice(b"SAUCE00");
+        push_cp437_field(&mut result, &self.title, 35);
+        push_cp437_field(&mut result, &self.author, 20);
+        push_cp437_field(&mut result, &self.group, 20);
+        push_cp437_field(&mut result, &self.date, 8);
+        result.extend_from_slice(&(file_size.min(u32::MAX as usize) as u32).to_le_bytes());
+        result.push(self.data_type);
+        result.push(self.file_type);
+        result.extend_from_slice(&self.width.to_le_bytes());
+        result.extend_from_slice(&self.height.to_le_bytes());
+        result.extend_from_slice(&[0; 4]);
+        result.push(self.comments.len().min(255) as u8);
+        result.push(self.flags);
+
+        // The font name is the only string which is padded with zeros rather than spaces
+        let font: Vec<u8> = self.font.chars().map(cp437_byte).take(22).collect();
+        result.extend_from_slice(&font);
+        result.resize(result.len() + 22 - font.len(), 0);
+
+        result
+    }
+}
+
+/// Convert a character to its codepage byte, characters which can not be represented become '?'
+fn cp437_byte(c: char) -> u8 {
+    VGAChar::from_char(c).map(|c| c.0).unwrap_or(b'?')
+}
+
+/// Decode a fixed width SAUCE text field, dropping the padding
+fn cp437_field(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| VGAChar(*b).to_char()).collect::<String>().trim_end_matches([' ', '\0']).to_string()
+}
+
+/// Append a fixed width SAUCE text field, truncating or padding it with spaces
+fn push_cp437_field(output: &mut Vec<u8>, text: &str, width: usize) {
+    let start = output.len();
+    output.extend(text.chars().map(cp437_byte).take(width));
+    output.resize(start + width, b' ');
+}
+
+/// Graphics state set by SGR escapes while reading ANSI art
+#[derive(Debug, Clone, Copy)]
+struct SgrState {
+    foreground: usize,
+    background: usize,
+    foreground_rgb: Option<(u8, u8, u8)>,
+    background_rgb: Option<(u8, u8, u8)>,
+    bold: bool,
+    blink: bool,
+    attributes: CellAttributes
+}
+
+impl SgrState {
+    const DEFAULT: SgrState = SgrState { foreground: 7, background: 0, foreground_rgb: None, background_rgb: None, bold: false, blink: false, attributes: CellAttributes::NONE };
+
+    /// Apply the parameters of an SGR escape
+    fn apply(&mut self, parameters: &[usize]) {
+        if parameters.is_empty() {
+            *self = Self::DEFAULT;
+        }
+
+        let mut i = 0;
+        while i < parameters.len() {
+            match parameters[i] {
+                0 => *self = Self::DEFAULT,
+                1 => self.bold = true,
+                2 => self.attributes |= CellAttributes::DIM,
+                4 => self.attributes |= CellAttributes::UNDERLINE,
+                5 | 6 => self.blink = true,
+                7 => self.attributes |= CellAttributes::REVERSE,
+                9 => self.attributes |= CellAttributes::STRIKETHROUGH,
+                22 => {
+                    self.bold = false;
+                    self.attributes = self.attributes.difference(CellAttributes::DIM);
+                }
+                24 => self.attributes = self.attributes.difference(CellAttributes::UNDERLINE),
+                25 => self.blink = false,
+                27 => self.attributes = self.attributes.difference(CellAttributes::REVERSE),
+                29 => self.attributes = self.attributes.difference(CellAttributes::STRIKETHROUGH),
+                n @ 30..=37 => {
+                    self.foreground = n - 30;
+                    self.foreground_rgb = None;
+                }
+                39 => {
+                    self.foreground = 7;
+                    self.foreground_rgb = None;
+                }
+                n @ 40..=47 => {
+                    self.background = n - 40;
+                    self.background_rgb = None;
+                }
+                49 => {
+                    self.background = 0;
+                    self.background_rgb = None;
+                }
+                n @ 90..=97 => {
+                    self.foreground = n - 90 + 8;
+                    self.foreground_rgb = None;
+                }
+                n @ 100..=107 => {
+                    self.background = n - 100 + 8;
+                    self.background_rgb = None;
+                }
+                n @ (38 | 48) => {
+                    // Extended colors are either 5;index or 2;r;g;b
+                    let rgb = match parameters.get(i + 1) {
+                        Some(2) if i + 4 < parameters.len() => {
+                            let channel = |j: usize| parameters[i + j].min(255) as u8;
+                            let rgb = (channel(2), channel(3), channel(4));
+                            i += 4;
+                            Some(rgb)
+                        }
+                        Some(5) if i + 2 < parameters.len() => {
+                            i += 2;
+                            Some(xterm_color(parameters[i]))
+                        }
+                        _ => None
+                    };
+
+                    if n == 38 {
+                        self.foreground_rgb = rgb;
+                    }
+                    else {
+                        self.background_rgb = rgb;
+                    }
+                }
+                _ => ()
+            }
+
+            i += 1;
+        }
+    }
+
+    /// Get the style of cells drawn with this state
+    fn style(&self, ice_colors: bool) -> CellStyle {
+        let bright_background = self.background >= 8 || (ice_colors && self.blink);
+
+        let foreground = match self.foreground_rgb {
+            Some((r, g, b)) => Color::Rgb(r, g, b),
+            None => Color::Named(CharacterColor::from_ansi_index(self.foreground, self.bold || self.foreground >= 8))
+        };
+        let background = match self.background_rgb {
+            Some((r, g, b)) => Color::Rgb(r, g, b),
+            None => Color::Named(CharacterColor::from_ansi_index(self.background, bright_background))
+        };
+
+        let mut attributes = self.attributes;
+        if self.blink && !ice_colors {
+            attributes |= CellAttributes::BLINK;
+        }
+
+        CellStyle::from_colors(foreground, background).attributes(attributes)
+    }
+}
+
+/// Get the RGB value of a color in the xterm 256 color palette
+fn xterm_color(index: usize) -> (u8, u8, u8) {
+    match index {
+        0..=15 => Palette::VGA.get(CharacterColor::from_ansi_index(index, index >= 8)),
+        16..=231 => {
+            let level = |v: usize| if v == 0 { 0 } else { (55 + v * 40) as u8 };
+            let i = index - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let gray = (8 + (index.min(255) - 232) * 10) as u8;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// A piece of ANSI art read into a text buffer screen
+pub struct AnsiArt {
+    pub screen: TextBufferScreen,
+    pub sauce: Option<Sauce>
+}
+
+impl AnsiArt {
+    /// Load an ANSI art file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        Ok(Self::decode(&std::fs::read(path).map_err(|e| e.to_string())?))
+    }
+
+    /// Save the art as an ANSI file, with its SAUCE record if it has one
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.encode()).map_err(|e| e.to_string())
+    }
+
+    /// Decode the contents of an ANSI art file, the width comes from the SAUCE record if there is one
+    pub fn decode(bytes: &[u8]) -> Self {
+        let (sauce, content) = match Sauce::parse(bytes) {
+            Some((sauce, end)) => (Some(sauce), &bytes[..end]),
+            None => (None, bytes)
+        };
+
+        let width = match &sauce {
+            Some(sauce) if sauce.width > 0 => (sauce.width as usize).min(ANSI_MAX_COLUMNS),
+            _ => ANSI_DEFAULT_WIDTH
+        };
+        let ice_colors = sauce.as_ref().map(|s| s.ice_colors()).unwrap_or(false);
+
+        let mut rows: Vec<Vec<Cell>> = Vec::new();
+        let mut state = SgrState::DEFAULT;
+        let (mut x, mut y) = (0usize, 0usize);
+        let mut saved = (0, 0);
+        let mut i = 0;
+
+        while i < content.len() {
+            match content[i] {
+                // Anything after an end of file character is metadata
+                0x1A => break,
+                b'\r' => x = 0,
+                b'\n' => {
+                    x = 0;
+                    y = (y + 1).min(ANSI_MAX_ROWS - 1);
+                }
+                b'\t' => x = ((x / 8 + 1) * 8).min(width),
+                0x1B if content.get(i + 1) == Some(&b'[') => {
+                    let Some(end) = content[i + 2..].iter().position(|b| (0x40..=0x7e).contains(b)) else { break };
+                    let end = i + 2 + end;
+
+                    let text = std::str::from_utf8(&content[i + 2..end]).unwrap_or("");
+                    let parameters: Vec<usize> = if text.is_empty() { Vec::new() } else { text.split(';').map(|p| p.parse().unwrap_or(0)).collect() };
+                    let count = parameters.first().copied().unwrap_or(1).max(1);
+
+                    match content[end] {
+                        b'm' => state.apply(&parameters),
+                        b'A' => y = y.saturating_sub(count),
+                        b'B' => y = (y + count).min(ANSI_MAX_ROWS - 1),
+                        b'C' => x = (x + count).min(width - 1),
+                        b'D' => x = x.min(width - 1).saturating_sub(count),
+                        b'H' | b'f' => {
+                            y = (parameters.first().copied().unwrap_or(1).max(1) - 1).min(ANSI_MAX_ROWS - 1);
+                            x = (parameters.get(1).copied().unwrap_or(1).max(1) - 1).min(width - 1);
+                        }
+                        b's' => saved = (x, y),
+                        b'u' => (x, y) = saved,
+                        b'J' if parameters.first() == Some(&2) => {
+                            rows.clear();
+                            (x, y) = (0, 0);
+                        }
+                        b'K' => {
+                            if let Some(row) = rows.get_mut(y) {
+                                row.iter_mut().skip(x).for_each(|c| *c = Cell::blank());
+                            }
+                        }
+                        _ => ()
+                    }
+
+                    i = end;
+                }
+                byte => {
+                    // Wrapping is deferred until the next character, so the line break written after a full line does not leave a gap
+                    if x >= width {
+                        x = 0;
+                        y = (y + 1).min(ANSI_MAX_ROWS - 1);
+                    }
+
+                    if rows.len() <= y {
+                        rows.resize(y + 1, vec![Cell::blank(); width]);
+                    }
+                    rows[y][x] = Cell::new(VGAChar(byte), state.style(ice_colors));
+                    x += 1;
+                }
+            }
+
+            i += 1;
+        }
+
+        let height = match &sauce {
+            Some(sauce) => rows.len().max((sauce.height as usize).min(ANSI_MAX_ROWS)),
+            None => rows.len()
+        };
+
+        let mut screen = TextBufferScreen::new(width, height);
+        for (y, row) in rows.iter().enumerate() {
+            screen.write_data(TextBufferPos { x: 0, y: y as isize }, row);
+        }
+
+        Self { screen, sauce }
+    }
+
+    /// Encode the art as the contents of an ANSI file
+    pub fn encode(&self) -> Vec<u8> {
+        Self::encode_buffer(&self.screen, self.screen.palette(), self.sauce.as_ref())
+    }
+
+    /// Encode any text buffer as the contents of an ANSI file. Exact colors are reduced to the closest named color in the palette, and bright backgrounds switch the SAUCE record to iCE colors, in which case blinking is dropped.
+    /// A SAUCE record is added for bright backgrounds even if none was given
+    pub fn encode_buffer(buffer: &(impl TextBufferInterface + ?Sized), palette: &Palette, sauce: Option<&Sauce>) -> Vec<u8> {
+        let (width, height) = (buffer.width(), buffer.height());
+        let cell = |x: usize, y: usize| buffer.char_ref(TextBufferPos { x: x as isize, y: y as isize }).copied().unwrap_or_default();
+        let named = |color: &Color| match color {
+            Color::Named(c) => *c,
+            Color::Rgb(r, g, b) => palette.nearest((*r, *g, *b))
+        };
+
+        let ice_colors = (0..height).any(|y| (0..width).any(|x| named(&cell(x, y).style.background).index() >= 8));
+
+        let mut result = Vec::new();
+        let mut current: Option<(CharacterColor, CharacterColor, CellAttributes)> = None;
+
+        for y in 0..height {
+            let length = (0..width).rev().find(|x| !cell(*x, y).is_blank()).map(|x| x + 1).unwrap_or(0);
+
+            for x in 0..length {
+                let c = cell(x, y);
+                let state = (named(&c.style.foreground), named(&c.style.background), c.style.attributes);
+
+                if current != Some(state) {
+                    let mut sequence = String::from("\x1b[0");
+                    if state.0.index() >= 8 {
+                        sequence += ";1";
+                    }
+                    if state.1.index() >= 8 || (!ice_colors && state.2.contains(CellAttributes::BLINK)) {
+                        sequence += ";5";
+                    }
+                    for (attribute, code) in [(CellAttributes::DIM, 2), (CellAttributes::UNDERLINE, 4), (CellAttributes::REVERSE, 7), (CellAttributes::STRIKETHROUGH, 9)] {
+                        if state.2.contains(attribute) {
+                            let _ = write!(sequence, ";{}", code);
+                        }
+                    }
+                    let _ = write!(sequence, ";{};{}m", 30 + state.0.ansi_index(), 40 + state.1.ansi_index());
+
+                    result.extend_from_slice(sequence.as_bytes());
+                    current = Some(state);
+                }
+
+                // Bytes the reader treats as control characters are written as spaces
+                result.push(match c.character.0 {
+                    b'\r' | b'\n' | b'\t' | 0x1A | 0x1B => b' ',
+                    b => b
+                });
+            }
+
+            // Full lines are followed by a line break too, readers defer the wrap after the last column so the break does not add a blank line
+            if y + 1 < height {
+                result.extend_from_slice(b"\r\n");
+            }
+        }
+
+        result.extend_from_slice(b"\x1b[0m");
+
+        // Bright backgrounds are written as blink, which only reads back as a background with the iCE colors flag, so they always need a record
+        let sauce = match sauce {
+            Some(sauce) => Some(sauce.clone()),
+            None if ice_colors => Some(Sauce::new(width, height)),
+            None => None
+        };
+
+        if let Some(mut sauce) = sauce {
+            sauce.width = width.min(u16::MAX as usize) as u16;
+            sauce.height = height.min(u16::MAX as usize) as u16;
+            if ice_colors {
+                sauce.flags |= SAUCE_ICE_COLORS;
+            }
+
+            let content_length = result.len();
+            result.extend(sauce.encode(content_length));
+        }
+
+        result
+    }
+}
@@ -0,0 +1,5 @@
+pub mod ansi;
+pub use ansi::*;
+
+pub mod rexpaint;
+pub use rexpaint::*;
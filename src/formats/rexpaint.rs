@@ -0,0 +1,280 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::screen::{Cell, CellStyle, Color, Palette, TextBufferInterface, TextBufferPos, TextBufferScreen, VGAChar};
+
+/// Background color REXPaint uses to mark a cell as transparent
+pub const XP_TRANSPARENT: (u8, u8, u8) = (255, 0, 255);
+
+/// Version number written to the header of saved REXPaint files
+const XP_VERSION: i32 = -1;
+
+/// How colors read from a REXPaint file are turned into cell colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XpColors<'a> {
+    /// Keep the exact RGB value of every cell
+    Exact,
+    /// Use the closest named color in the given palette
+    Nearest(&'a Palette)
+}
+
+impl<'a> XpColors<'a> {
+    /// Convert an RGB value from a file to a cell color
+    fn convert(&self, rgb: (u8, u8, u8)) -> Color {
+        match self {
+            XpColors::Exact => Color::Rgb(rgb.0, rgb.1, rgb.2),
+            XpColors::Nearest(palette) => Color::Named(palette.nearest(rgb))
+        }
+    }
+}
+
+/// A single cell of a REXPaint layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XpCell {
+    pub character: VGAChar,
+    pub foreground: (u8, u8, u8),
+    pub background: (u8, u8, u8)
+}
+
+impl XpCell {
+    /// A transparent cell, which is what empty layer space is saved as
+    pub const TRANSPARENT: XpCell = XpCell { character: VGAChar(b' '), foreground: (0, 0, 0), background: XP_TRANSPARENT };
+
+    /// Check if the cell lets the layers below it show through
+    pub fn is_transparent(&self) -> bool {
+        self.background == XP_TRANSPARENT
+    }
+
+    /// Convert the cell to a text buffer cell
+    pub fn to_cell(&self, colors: XpColors) -> Cell {
+        Cell::new(self.character, CellStyle::from_colors(colors.convert(self.foreground), colors.convert(self.background)))
+    }
+
+    /// Convert a text buffer cell to a REXPaint cell, resolving named colors with the given palette. Attributes can not be stored and are dropped
+    pub fn from_cell(cell: &Cell, palette: &Palette) -> Self {
+        Self {
+            character: cell.character,
+            foreground: palette.resolve(&cell.style.foreground),
+            background: palette.resolve(&cell.style.background)
+        }
+    }
+}
+
+/// A single layer of a REXPaint image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XpLayer {
+    width: usize,
+    height: usize,
+    cells: Vec<XpCell>
+}
+
+impl XpLayer {
+    /// Construct a new, fully transparent layer
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![XpCell::TRANSPARENT; width * height]
+        }
+    }
+
+    /// Construct a layer from the contents of a text buffer, resolving named colors with the given palette
+    pub fn from_buffer(buffer: &(impl TextBufferInterface + ?Sized), palette: &Palette) -> Self {
+        let mut layer = Self::new(buffer.width(), buffer.height());
+
+        for y in 0..layer.height {
+            for x in 0..layer.width {
+                if let Some(cell) = buffer.char_ref(TextBufferPos { x: x as isize, y: y as isize }) {
+                    layer.cells[x + y * layer.width] = XpCell::from_cell(cell, palette);
+                }
+            }
+        }
+
+        layer
+    }
+
+    /// Get the width of the layer in cells
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the layer in cells
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the cell at the given position
+    pub fn get(&self, x: usize, y: usize) -> Option<&XpCell> {
+        if x < self.width && y < self.height {
+            self.cells.get(x + y * self.width)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the cell at the given position
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut XpCell> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(x + y * self.width)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Draw the layer onto a text buffer at the given position, transparent cells are skipped
+    pub fn blit(&self, target: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, colors: XpColors) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = &self.cells[x + y * self.width];
+
+                if !cell.is_transparent() {
+                    target.write_data(TextBufferPos { x: pos.x + x as isize, y: pos.y + y as isize }, &[cell.to_cell(colors)]);
+                }
+            }
+        }
+    }
+
+    /// Convert the layer to a text buffer screen the same size as the layer
+    pub fn to_screen(&self, colors: XpColors) -> TextBufferScreen {
+        let mut screen = TextBufferScreen::new(self.width, self.height);
+        self.blit(&mut screen, TextBufferPos { x: 0, y: 0 }, colors);
+
+        screen
+    }
+}
+
+/// An image made of layers of cells, stored on disk as a gzip compressed REXPaint .xp file
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct XpImage {
+    pub layers: Vec<XpLayer>
+}
+
+impl XpImage {
+    /// Construct a new image without any layers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a single layer image from the contents of a text buffer
+    pub fn from_buffer(buffer: &(impl TextBufferInterface + ?Sized), palette: &Palette) -> Self {
+        Self {
+            layers: vec![XpLayer::from_buffer(buffer, palette)]
+        }
+    }
+
+    /// Construct a single layer image from a text buffer screen, using the screen's palette
+    pub fn from_screen(screen: &TextBufferScreen) -> Self {
+        Self::from_buffer(screen, screen.palette())
+    }
+
+    /// Load a REXPaint file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        Self::decode(&std::fs::read(path).map_err(|e| e.to_string())?)
+    }
+
+    /// Save the image as a REXPaint file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.encode()?).map_err(|e| e.to_string())
+    }
+
+    /// Decode the gzip compressed contents of a REXPaint file
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut data = Vec::new();
+        flate2::read::GzDecoder::new(bytes).read_to_end(&mut data).map_err(|e| e.to_string())?;
+
+        let mut offset: usize = 0;
+        let mut next = |count: usize| -> Result<&[u8], String> {
+            let slice = offset.checked_add(count).and_then(|end| data.get(offset..end)).ok_or("Truncated REXPaint file")?;
+            offset += count;
+            Ok(slice)
+        };
+        let read_i32 = |bytes: &[u8]| i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let read_size = |bytes: &[u8]| usize::try_from(read_i32(bytes)).map_err(|_| "Negative size in REXPaint file".to_string());
+
+        // The version is always negative, and nothing in the layout depends on it
+        let _version = read_i32(next(4)?);
+        let layer_count = read_size(next(4)?)?;
+
+        let mut layers = Vec::new();
+
+        for _ in 0..layer_count {
+            let width = read_size(next(4)?)?;
+            let height = read_size(next(4)?)?;
+
+            // Check the cells are all there before allocating, so a corrupt header can not ask for an enormous layer
+            let length = width.checked_mul(height).and_then(|cells| cells.checked_mul(10)).ok_or("REXPaint layer is too large")?;
+            let cells = next(length)?;
+            let mut layer = XpLayer::new(width, height);
+
+            // Cells are stored column by column
+            for (i, cell) in cells.chunks_exact(10).enumerate() {
+                let (x, y) = (i / height, i % height);
+                let code = u32::from_le_bytes([cell[0], cell[1], cell[2], cell[3]]);
+
+                layer.cells[x + y * width] = XpCell {
+                    character: VGAChar(u8::try_from(code).unwrap_or(b'?')),
+                    foreground: (cell[4], cell[5], cell[6]),
+                    background: (cell[7], cell[8], cell[9])
+                };
+            }
+
+            layers.push(layer);
+        }
+
+        Ok(Self { layers })
+    }
+
+    /// Encode the image as the gzip compressed contents of a REXPaint file
+    pub fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&XP_VERSION.to_le_bytes());
+        data.extend_from_slice(&(self.layers.len() as i32).to_le_bytes());
+
+        for layer in &self.layers {
+            data.extend_from_slice(&(layer.width as i32).to_le_bytes());
+            data.extend_from_slice(&(layer.height as i32).to_le_bytes());
+
+            for x in 0..layer.width {
+                for y in 0..layer.height {
+                    let cell = &layer.cells[x + y * layer.width];
+
+                    data.extend_from_slice(&(cell.character.0 as u32).to_le_bytes());
+                    data.extend_from_slice(&[cell.foreground.0, cell.foreground.1, cell.foreground.2]);
+                    data.extend_from_slice(&[cell.background.0, cell.background.1, cell.background.2]);
+                }
+            }
+        }
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&data).map_err(|e| e.to_string())?;
+        encoder.finish().map_err(|e| e.to_string())
+    }
+
+    /// Get the size of the image in cells, which is the size of the largest layer
+    pub fn size(&self) -> (usize, usize) {
+        self.layers.iter().fold((0, 0), |(w, h), layer| (w.max(layer.width), h.max(layer.height)))
+    }
+
+    /// Draw every layer onto a text buffer at the given position, from the bottom layer up
+    pub fn blit(&self, target: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, colors: XpColors) {
+        for layer in &self.layers {
+            layer.blit(target, pos, colors);
+        }
+    }
+
+    /// Flatten every layer into a single text buffer screen
+    pub fn to_screen(&self, colors: XpColors) -> TextBufferScreen {
+        let (width, height) = self.size();
+        let mut screen = TextBufferScreen::new(width, height);
+        self.blit(&mut screen, TextBufferPos { x: 0, y: 0 }, colors);
+
+        screen
+    }
+
+    /// Convert each layer to its own text buffer screen
+    pub fn to_screens(&self, colors: XpColors) -> Vec<TextBufferScreen> {
+        self.layers.iter().map(|layer| layer.to_screen(colors)).collect()
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::{InputEvent, Key, KeyModifiers};
+
+/// A key pressed together with an exact set of modifiers, written as text such as "ctrl+shift+s", "up" or "f5"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: Key,
+    pub modifiers: KeyModifiers
+}
+
+/// Names of keys which can not be written as their own character in a binding
+const KEY_NAMES: [(&str, Key); 18] = [
+    ("enter", Key::Enter),
+    ("escape", Key::Escape),
+    ("backspace", Key::Backspace),
+    ("tab", Key::Tab),
+    ("delete", Key::Delete),
+    ("insert", Key::Insert),
+    ("up", Key::Up),
+    ("down", Key::Down),
+    ("left", Key::Left),
+    ("right", Key::Right),
+    ("home", Key::Home),
+    ("end", Key::End),
+    ("pageup", Key::PageUp),
+    ("pagedown", Key::PageDown),
+    ("space", Key::Char(' ')),
+    ("plus", Key::Char('+')),
+    ("comma", Key::Char(',')),
+    ("hash", Key::Char('#'))
+];
+
+impl KeyChord {
+    pub const fn new(key: Key, modifiers: KeyModifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// A chord of a key with no modifiers
+    pub const fn key(key: Key) -> Self {
+        Self::new(key, KeyModifiers::NONE)
+    }
+
+    /// Get the chord pressed by a key down event, repeats are ignored so this can be used to capture a new binding
+    pub fn from_event(event: &InputEvent) -> Option<Self> {
+        match event {
+            InputEvent::KeyDown { key, modifiers, repeat: false } => Some(Self::new(*key, *modifiers)),
+            _ => None
+        }
+    }
+
+    /// Parse a chord such as "ctrl+s", "shift+tab" or "a", modifier and key names are not case sensitive
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts: Vec<&str> = text.trim().split('+').map(str::trim).collect();
+        let name = parts.pop().filter(|name| !name.is_empty()).ok_or_else(|| format!("Missing key in binding \"{}\"", text))?;
+        let mut modifiers = KeyModifiers::NONE;
+
+        for part in parts {
+            match part.to_ascii_lowercase().as_str() {
+                "shift" => modifiers.shift = true,
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" => modifiers.alt = true,
+                _ => return Err(format!("Unknown modifier \"{}\" in binding \"{}\"", part, text))
+            }
+        }
+
+        let lower = name.to_ascii_lowercase();
+        let mut chars = lower.chars();
+        let key = match (chars.next(), chars.next()) {
+            (Some(c), None) => Key::Char(c),
+            _ => KEY_NAMES.iter().find(|(n, _)| *n == lower).map(|(_, key)| *key)
+                .or_else(|| lower.strip_prefix('f').and_then(|n| n.parse().ok()).filter(|n| (1..=24).contains(n)).map(Key::Function))
+                .ok_or_else(|| format!("Unknown key \"{}\" in binding \"{}\"", name, text))?
+        };
+
+        Ok(Self::new(key, modifiers))
+    }
+
+    /// Returns true if an event is a press of this chord, the modifiers held must match exactly
+    pub fn matches(&self, event: &InputEvent) -> bool {
+        matches!(event, InputEvent::KeyDown { key, modifiers, .. } if *key == self.key && *modifiers == self.modifiers)
+    }
+}
+
+impl std::fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.shift {
+            write!(f, "shift+")?;
+        }
+
+        match KEY_NAMES.iter().find(|(_, key)| *key == self.key) {
+            Some((name, _)) => write!(f, "{}", name),
+            None => match self.key {
+                Key::Char(c) => write!(f, "{}", c),
+                Key::Function(n) => write!(f, "f{}", n),
+                _ => unreachable!()
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KeyChord::parse(s)
+    }
+}
+
+impl From<Key> for KeyChord {
+    fn from(key: Key) -> Self {
+        KeyChord::key(key)
+    }
+}
+
+/// How held keys repeating are treated for an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepeatPolicy {
+    /// Only the first press counts, suited to actions such as jumping or opening a menu
+    #[default]
+    Ignore,
+    /// Each repeat counts as another press, suited to moving through menus or text
+    Repeat
+}
+
+/// An action along with the chords bound to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Action {
+    name: String,
+    chords: Vec<KeyChord>,
+    repeat: RepeatPolicy
+}
+
+/// Maps key chords to named actions and tracks which actions were pressed, held and released each frame.
+/// Feed every input event to handle_event and call next_frame once the frame's input has been read
+#[derive(Debug, Clone, Default)]
+pub struct InputMap {
+    actions: Vec<Action>,
+    /// Keys currently down along with the action each one started
+    held: Vec<(Key, usize)>,
+    pressed: HashSet<usize>,
+    released: HashSet<usize>
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind a chord to an action, creating the action if it does not exist
+    pub fn bind(mut self, action: &str, chord: impl Into<KeyChord>) -> Self {
+        self.add_binding(action, chord.into());
+        self
+    }
+
+    /// Set how repeats of the keys held for an action are treated
+    pub fn repeat(mut self, action: &str, policy: RepeatPolicy) -> Self {
+        self.set_repeat(action, policy);
+        self
+    }
+
+    fn action_index(&mut self, action: &str) -> usize {
+        match self.actions.iter().position(|a| a.name == action) {
+            Some(index) => index,
+            None => {
+                self.actions.push(Action { name: action.to_string(), chords: Vec::new(), repeat: RepeatPolicy::default() });
+                self.actions.len() - 1
+            }
+        }
+    }
+
+    fn find(&self, action: &str) -> Option<&Action> {
+        self.actions.iter().find(|a| a.name == action)
+    }
+
+    fn is_set(&self, action: &str, set: &HashSet<usize>) -> bool {
+        self.actions.iter().position(|a| a.name == action).is_some_and(|index| set.contains(&index))
+    }
+
+    pub fn add_binding(&mut self, action: &str, chord: KeyChord) {
+        let index = self.action_index(action);
+        if !self.actions[index].chords.contains(&chord) {
+            self.actions[index].chords.push(chord);
+        }
+    }
+
+    /// Replace all of the chords bound to an action
+    pub fn set_bindings(&mut self, action: &str, chords: &[KeyChord]) {
+        let index = self.action_index(action);
+        self.actions[index].chords = chords.to_vec();
+        self.held.retain(|(_, held)| *held != index);
+    }
+
+    /// Remove a chord from every action it is bound to
+    pub fn unbind(&mut self, chord: KeyChord) {
+        for action in self.actions.iter_mut() {
+            action.chords.retain(|c| *c != chord);
+        }
+    }
+
+    pub fn set_repeat(&mut self, action: &str, policy: RepeatPolicy) {
+        let index = self.action_index(action);
+        self.actions[index].repeat = policy;
+    }
+
+    /// Get the names of every action in the order they were added
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.iter().map(|a| a.name.as_str())
+    }
+
+    pub fn bindings(&self, action: &str) -> &[KeyChord] {
+        self.find(action).map(|a| a.chords.as_slice()).unwrap_or(&[])
+    }
+
+    /// Get the actions a chord is bound to
+    pub fn actions_for(&self, chord: KeyChord) -> impl Iterator<Item = &str> {
+        self.actions.iter().filter(move |a| a.chords.contains(&chord)).map(|a| a.name.as_str())
+    }
+
+    /// Get a line for each action listing its bindings, such as "jump: space, w", for showing in a rebinding menu
+    pub fn binding_labels(&self) -> Vec<String> {
+        self.actions.iter().map(|a| {
+            let chords: Vec<String> = a.chords.iter().map(|c| c.to_string()).collect();
+            format!("{}: {}", a.name, chords.join(", "))
+        }).collect()
+    }
+
+    /// Update the action states from an input event, returns true if the event was a key bound to an action
+    pub fn handle_event(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::KeyDown { key, modifiers, repeat } => {
+                let chord = KeyChord::new(*key, *modifiers);
+                let mut used = false;
+
+                for (index, action) in self.actions.iter().enumerate() {
+                    if !action.chords.contains(&chord) {
+                        continue;
+                    }
+                    used = true;
+
+                    if !*repeat || action.repeat == RepeatPolicy::Repeat {
+                        self.pressed.insert(index);
+                    }
+                    if !self.held.contains(&(*key, index)) {
+                        self.held.push((*key, index));
+                    }
+                }
+
+                used
+            }
+            // Modifiers may have been let go first, so releasing the key ends every action it started
+            InputEvent::KeyUp { key, .. } => {
+                let mut used = false;
+
+                for (_, index) in self.held.iter().filter(|(k, _)| k == key) {
+                    self.released.insert(*index);
+                    used = true;
+                }
+                self.held.retain(|(k, _)| k != key);
+
+                used
+            }
+            _ => false
+        }
+    }
+
+    /// Clear the pressed and released states, call this at the end of each frame
+    pub fn next_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+
+    /// Forget every held key, used when the window loses focus and key releases may be missed
+    pub fn release_all(&mut self) {
+        self.released.extend(self.held.drain(..).map(|(_, index)| index));
+    }
+
+    /// Returns true if the action was pressed this frame
+    pub fn pressed(&self, action: &str) -> bool {
+        self.is_set(action, &self.pressed)
+    }
+
+    /// Returns true if a key bound to the action is down
+    pub fn held(&self, action: &str) -> bool {
+        self.actions.iter().position(|a| a.name == action).is_some_and(|index| self.held.iter().any(|(_, held)| *held == index))
+    }
+
+    /// Returns true if the action was released this frame
+    pub fn released(&self, action: &str) -> bool {
+        self.is_set(action, &self.released)
+    }
+
+    /// Get the actions pressed this frame, in the order they were added
+    pub fn pressed_actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.iter().enumerate().filter(|(index, _)| self.pressed.contains(index)).map(|(_, a)| a.name.as_str())
+    }
+
+    /// Apply bindings from config text, each line is "action = chord, chord" and lines starting with # are comments.
+    /// Actions listed replace their current bindings, so a file of user changes can be loaded over the defaults
+    pub fn apply_config(&mut self, text: &str) -> Result<(), String> {
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (action, chords) = line.split_once('=').ok_or_else(|| format!("Expected \"action = keys\" on line {}", number + 1))?;
+            let action = action.trim();
+            if action.is_empty() {
+                return Err(format!("Missing action name on line {}", number + 1));
+            }
+
+            let chords = chords.split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(KeyChord::parse)
+                .collect::<Result<Vec<_>, String>>()
+                .map_err(|e| format!("{} on line {}", e, number + 1))?;
+
+            self.set_bindings(action, &chords);
+        }
+
+        Ok(())
+    }
+
+    /// Get the bindings as config text which apply_config reads
+    pub fn to_config(&self) -> String {
+        let mut text = String::new();
+
+        for action in self.actions.iter() {
+            let chords: Vec<String> = action.chords.iter().map(|c| c.to_string()).collect();
+            text.push_str(&format!("{} = {}\n", action.name, chords.join(", ")));
+        }
+
+        text
+    }
+
+    /// Load bindings from a config file over the current ones
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
+        self.apply_config(&std::fs::read_to_string(path).map_err(|e| e.to_string())?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.to_config()).map_err(|e| e.to_string())
+    }
+}
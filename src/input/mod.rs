@@ -0,0 +1,87 @@
+pub mod bindings;
+pub use bindings::*;
+
+pub mod mouse;
+pub use mouse::*;
+
+#[cfg(feature = "sdl")]
+pub mod sdl;
+#[cfg(feature = "sdl")]
+pub use sdl::*;
+
+#[cfg(unix)]
+pub mod terminal;
+#[cfg(unix)]
+pub use terminal::*;
+
+/// Keys recognised by the engine, independent of the platform they were read from. Letters are always reported in lower case with the shift modifier set separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Escape,
+    Backspace,
+    Tab,
+    Delete,
+    Insert,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Function(u8)
+}
+
+/// Modifier keys held while a key was pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool
+}
+
+impl KeyModifiers {
+    pub const NONE: KeyModifiers = KeyModifiers { shift: false, ctrl: false, alt: false };
+    pub const SHIFT: KeyModifiers = KeyModifiers { shift: true, ctrl: false, alt: false };
+    pub const CTRL: KeyModifiers = KeyModifiers { shift: false, ctrl: true, alt: false };
+    pub const ALT: KeyModifiers = KeyModifiers { shift: false, ctrl: false, alt: true };
+
+    /// Returns true if no modifiers are held
+    pub const fn is_empty(&self) -> bool {
+        !(self.shift || self.ctrl || self.alt)
+    }
+}
+
+/// Input events delivered to the game loop, these are produced by both the window and terminal input layers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputEvent {
+    KeyDown { key: Key, modifiers: KeyModifiers, repeat: bool },
+    KeyUp { key: Key, modifiers: KeyModifiers },
+    /// Text entered by the user, separate from the key presses which produced it
+    Text(String),
+    /// The mouse moved, was clicked or was scrolled, with the position in cells
+    Mouse(MouseEvent),
+    /// The display was resized, the size is in pixels for windows and in cells for terminals
+    Resize(usize, usize),
+    Quit
+}
+
+impl InputEvent {
+    /// Construct a key down event which is not a repeat
+    pub const fn key(key: Key, modifiers: KeyModifiers) -> Self {
+        InputEvent::KeyDown { key, modifiers, repeat: false }
+    }
+
+    /// Construct a mouse event at a position in cells
+    pub const fn mouse(kind: MouseEventKind, pos: crate::screen::TextBufferPos) -> Self {
+        InputEvent::Mouse(MouseEvent::new(kind, pos))
+    }
+
+    /// Returns true if the event is a press of the given key, ignoring modifiers
+    pub fn is_key(&self, key: Key) -> bool {
+        matches!(self, InputEvent::KeyDown { key: k, .. } if *k == key)
+    }
+}
@@ -0,0 +1,159 @@
+use crate::scaling::Viewport;
+use crate::screen::TextBufferPos;
+
+use super::InputEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right
+}
+
+impl MouseButton {
+    const ALL: [MouseButton; 3] = [MouseButton::Left, MouseButton::Middle, MouseButton::Right];
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// What happened to the mouse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseEventKind {
+    /// The pointer moved into a different cell with no buttons held
+    Moved,
+    Pressed(MouseButton),
+    Released(MouseButton),
+    /// The pointer moved into a different cell while a button was held
+    Dragged(MouseButton),
+    /// The wheel was turned, positive y is away from the user and positive x is to the right
+    Wheel { x: i32, y: i32 }
+}
+
+/// A mouse event at a position in cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub pos: TextBufferPos
+}
+
+impl MouseEvent {
+    pub const fn new(kind: MouseEventKind, pos: TextBufferPos) -> Self {
+        Self { kind, pos }
+    }
+
+    /// Get the event with its position relative to the given origin, used to pass events to elements drawn inside of a view
+    pub const fn relative_to(&self, origin: TextBufferPos) -> Self {
+        Self::new(self.kind, TextBufferPos { x: self.pos.x - origin.x, y: self.pos.y - origin.y })
+    }
+}
+
+/// Converts mouse positions in pixels into cells and turns pointer and button changes into mouse events.
+/// The pixel scale is a percentage in the same form the renderers take
+#[derive(Debug, Clone)]
+pub struct MouseTracker {
+    cell_size: (usize, usize),
+    pixel_scale: usize,
+    /// Top left corner of the grid in drawable pixels
+    origin: (i32, i32),
+    /// Percentage of drawable pixels to the window pixels mouse positions are given in
+    hidpi_scale: usize,
+    pos: Option<TextBufferPos>,
+    buttons: [bool; 3]
+}
+
+impl MouseTracker {
+    pub const fn new(cell_size: (usize, usize), pixel_scale: usize) -> Self {
+        Self {
+            cell_size,
+            pixel_scale,
+            origin: (0, 0),
+            hidpi_scale: 100,
+            pos: None,
+            buttons: [false; 3]
+        }
+    }
+
+    /// Change the size cells are drawn at, this should follow any change to the character map or pixel scale
+    pub fn set_scale(&mut self, cell_size: (usize, usize), pixel_scale: usize) {
+        self.cell_size = cell_size;
+        self.pixel_scale = pixel_scale;
+    }
+
+    /// Follow a viewport from a scale mode, the hidpi scale is the percentage of drawable pixels to window pixels
+    pub fn set_viewport(&mut self, cell_size: (usize, usize), viewport: &Viewport, hidpi_scale: usize) {
+        self.cell_size = cell_size;
+        self.pixel_scale = viewport.pixel_scale;
+        self.origin = viewport.origin;
+        self.hidpi_scale = hidpi_scale;
+    }
+
+    pub fn cell_size(&self) -> (usize, usize) {
+        self.cell_size
+    }
+
+    pub fn pixel_scale(&self) -> usize {
+        self.pixel_scale
+    }
+
+    /// Get the cell containing a point given in window pixels, matching the cell edges the renderers draw
+    pub fn pixel_to_cell(&self, x: i32, y: i32) -> TextBufferPos {
+        let width = (self.cell_size.0 * self.pixel_scale).max(1) as isize;
+        let height = (self.cell_size.1 * self.pixel_scale).max(1) as isize;
+        let x = (x as isize * self.hidpi_scale as isize).div_euclid(100) - self.origin.0 as isize;
+        let y = (y as isize * self.hidpi_scale as isize).div_euclid(100) - self.origin.1 as isize;
+
+        TextBufferPos {
+            x: ((x + 1) * 100 - 1).div_euclid(width),
+            y: ((y + 1) * 100 - 1).div_euclid(height)
+        }
+    }
+
+    /// Get the cell the pointer was last seen in
+    pub fn position(&self) -> Option<TextBufferPos> {
+        self.pos
+    }
+
+    pub fn is_pressed(&self, button: MouseButton) -> bool {
+        self.buttons[button.index()]
+    }
+
+    /// Handle the pointer moving to a point in pixels, returns a hover or drag event if it moved into a different cell
+    pub fn motion(&mut self, x: i32, y: i32) -> Option<InputEvent> {
+        let pos = self.pixel_to_cell(x, y);
+        if self.pos == Some(pos) {
+            return None;
+        }
+
+        self.pos = Some(pos);
+        let kind = match MouseButton::ALL.into_iter().find(|b| self.is_pressed(*b)) {
+            Some(button) => MouseEventKind::Dragged(button),
+            None => MouseEventKind::Moved
+        };
+
+        Some(InputEvent::Mouse(MouseEvent::new(kind, pos)))
+    }
+
+    /// Handle a button being pressed or released at a point in pixels
+    pub fn button(&mut self, button: MouseButton, pressed: bool, x: i32, y: i32) -> Option<InputEvent> {
+        let pos = self.pixel_to_cell(x, y);
+        self.pos = Some(pos);
+        self.buttons[button.index()] = pressed;
+
+        let kind = if pressed { MouseEventKind::Pressed(button) } else { MouseEventKind::Released(button) };
+        Some(InputEvent::Mouse(MouseEvent::new(kind, pos)))
+    }
+
+    /// Handle the wheel turning, the event is placed where the pointer was last seen so nothing is returned before the pointer has been seen
+    pub fn wheel(&mut self, x: i32, y: i32) -> Option<InputEvent> {
+        self.pos.map(|pos| InputEvent::Mouse(MouseEvent::new(MouseEventKind::Wheel { x, y }, pos)))
+    }
+}
+
+impl std::default::Default for MouseTracker {
+    /// A tracker for the bundled 9x16 code page at its natural size
+    fn default() -> Self {
+        Self::new((9, 16), 100)
+    }
+}
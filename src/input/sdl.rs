@@ -0,0 +1,94 @@
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::{MouseButton as SdlMouseButton, MouseWheelDirection};
+
+use super::{InputEvent, Key, KeyModifiers, MouseButton, MouseTracker};
+
+/// Convert an sdl2 keycode to an engine key
+pub fn key_from_keycode(keycode: Keycode) -> Option<Key> {
+    Some(match keycode {
+        Keycode::Return | Keycode::KpEnter => Key::Enter,
+        Keycode::Escape => Key::Escape,
+        Keycode::Backspace => Key::Backspace,
+        Keycode::Tab => Key::Tab,
+        Keycode::Delete => Key::Delete,
+        Keycode::Insert => Key::Insert,
+        Keycode::Up => Key::Up,
+        Keycode::Down => Key::Down,
+        Keycode::Left => Key::Left,
+        Keycode::Right => Key::Right,
+        Keycode::Home => Key::Home,
+        Keycode::End => Key::End,
+        Keycode::PageUp => Key::PageUp,
+        Keycode::PageDown => Key::PageDown,
+        Keycode::F1 => Key::Function(1),
+        Keycode::F2 => Key::Function(2),
+        Keycode::F3 => Key::Function(3),
+        Keycode::F4 => Key::Function(4),
+        Keycode::F5 => Key::Function(5),
+        Keycode::F6 => Key::Function(6),
+        Keycode::F7 => Key::Function(7),
+        Keycode::F8 => Key::Function(8),
+        Keycode::F9 => Key::Function(9),
+        Keycode::F10 => Key::Function(10),
+        Keycode::F11 => Key::Function(11),
+        Keycode::F12 => Key::Function(12),
+        // Printable keycodes in sdl2 share their values with ascii
+        _ => {
+            let code = keycode as i32;
+            if (32..127).contains(&code) {
+                Key::Char(code as u8 as char)
+            }
+            else {
+                return None;
+            }
+        }
+    })
+}
+
+/// Convert sdl2 modifier flags to engine key modifiers
+pub fn modifiers_from_keymod(keymod: Mod) -> KeyModifiers {
+    KeyModifiers {
+        shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+        alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD)
+    }
+}
+
+impl InputEvent {
+    /// Convert an sdl2 event to an input event, returns None for events the engine does not handle
+    pub fn from_sdl(event: &Event) -> Option<Self> {
+        match event {
+            Event::Quit { .. } => Some(InputEvent::Quit),
+            Event::KeyDown { keycode: Some(keycode), keymod, repeat, .. } =>
+                Some(InputEvent::KeyDown { key: key_from_keycode(*keycode)?, modifiers: modifiers_from_keymod(*keymod), repeat: *repeat }),
+            Event::KeyUp { keycode: Some(keycode), keymod, .. } =>
+                Some(InputEvent::KeyUp { key: key_from_keycode(*keycode)?, modifiers: modifiers_from_keymod(*keymod) }),
+            Event::TextInput { text, .. } => Some(InputEvent::Text(text.clone())),
+            Event::Window { win_event: WindowEvent::Resized(width, height), .. } =>
+                Some(InputEvent::Resize(*width as usize, *height as usize)),
+            _ => None
+        }
+    }
+}
+
+impl MouseTracker {
+    /// Convert an sdl2 mouse event to a mouse input event in cells, returns None for other events and motion within a cell
+    pub fn from_sdl(&mut self, event: &Event) -> Option<InputEvent> {
+        let button = |button: &SdlMouseButton| match button {
+            SdlMouseButton::Left => Some(MouseButton::Left),
+            SdlMouseButton::Middle => Some(MouseButton::Middle),
+            SdlMouseButton::Right => Some(MouseButton::Right),
+            _ => None
+        };
+
+        match event {
+            Event::MouseMotion { x, y, .. } => self.motion(*x, *y),
+            Event::MouseButtonDown { mouse_btn, x, y, .. } => self.button(button(mouse_btn)?, true, *x, *y),
+            Event::MouseButtonUp { mouse_btn, x, y, .. } => self.button(button(mouse_btn)?, false, *x, *y),
+            Event::MouseWheel { x, y, direction: MouseWheelDirection::Flipped, .. } => self.wheel(-*x, -*y),
+            Event::MouseWheel { x, y, .. } => self.wheel(*x, *y),
+            _ => None
+        }
+    }
+}
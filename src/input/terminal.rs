@@ -0,0 +1,237 @@
+use std::io::Read;
+
+use super::{InputEvent, Key, KeyModifiers};
+
+/// Get the size of the terminal attached to stdout in cells
+pub fn terminal_size() -> Option<(usize, usize)> {
+    let mut size = libc::winsize { ws_row: 0, ws_col: 0, ws_xpixel: 0, ws_ypixel: 0 };
+
+    // Safety: TIOCGWINSZ only writes to the winsize struct passed to it
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+
+    if result == 0 && size.ws_col > 0 && size.ws_row > 0 {
+        Some((size.ws_col as usize, size.ws_row as usize))
+    }
+    else {
+        None
+    }
+}
+
+/// Input layer reading key presses from stdin with the terminal in raw mode, the terminal is restored when this is dropped
+pub struct TerminalInput {
+    original: libc::termios,
+    size: Option<(usize, usize)>,
+    pending: Vec<u8>
+}
+
+impl TerminalInput {
+    /// Put the terminal into raw, non blocking mode
+    pub fn new() -> Result<Self, String> {
+        // Safety: termios is plain data, and is fully initialized by tcgetattr before it is read
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+
+        let mut raw = original;
+        unsafe { libc::cfmakeraw(&mut raw) };
+
+        // Reads return immediately with whatever input is available
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+
+        Ok(Self {
+            original,
+            size: terminal_size(),
+            pending: Vec::new()
+        })
+    }
+
+    /// Get the size of the terminal in cells as of the last poll
+    pub fn size(&self) -> Option<(usize, usize)> {
+        self.size
+    }
+
+    /// Read all of the events which are currently available without blocking
+    pub fn poll_events(&mut self) -> Vec<InputEvent> {
+        let mut buffer = [0u8; 256];
+        let mut stdin = std::io::stdin().lock();
+
+        while let Ok(count) = stdin.read(&mut buffer) {
+            if count == 0 {
+                break;
+            }
+            self.pending.extend_from_slice(&buffer[..count]);
+        }
+
+        let (mut events, consumed) = parse_input(&self.pending);
+        self.pending.drain(..consumed);
+
+        let size = terminal_size();
+        if size != self.size {
+            self.size = size;
+            if let Some((width, height)) = size {
+                events.push(InputEvent::Resize(width, height));
+            }
+        }
+
+        events
+    }
+}
+
+impl Drop for TerminalInput {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
+
+/// Decode the modifier parameter of a CSI sequence, which is one more than a bitmask of shift, alt and ctrl
+fn csi_modifiers(parameter: Option<u32>) -> KeyModifiers {
+    let mask = parameter.unwrap_or(1).saturating_sub(1);
+
+    KeyModifiers {
+        shift: mask & 1 != 0,
+        alt: mask & 2 != 0,
+        ctrl: mask & 4 != 0
+    }
+}
+
+/// Parse the key produced by a CSI sequence given its parameters and final byte
+fn csi_key(parameters: &[Option<u32>], final_byte: u8) -> Option<(Key, KeyModifiers)> {
+    let modifiers = csi_modifiers(parameters.get(1).copied().flatten());
+
+    let key = match final_byte {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'Z' => return Some((Key::Tab, KeyModifiers::SHIFT)),
+        b'~' => match parameters.first().copied().flatten()? {
+            1 | 7 => Key::Home,
+            2 => Key::Insert,
+            3 => Key::Delete,
+            4 | 8 => Key::End,
+            5 => Key::PageUp,
+            6 => Key::PageDown,
+            n @ 11..=15 => Key::Function(n as u8 - 10),
+            n @ 17..=21 => Key::Function(n as u8 - 11),
+            n @ 23..=24 => Key::Function(n as u8 - 12),
+            _ => return None
+        },
+        _ => return None
+    };
+
+    Some((key, modifiers))
+}
+
+/// Convert a single byte or character read from the terminal to a key
+fn char_key(c: char) -> (Key, KeyModifiers) {
+    match c {
+        '\r' | '\n' => (Key::Enter, KeyModifiers::NONE),
+        '\t' => (Key::Tab, KeyModifiers::NONE),
+        '\x7f' | '\x08' => (Key::Backspace, KeyModifiers::NONE),
+        '\x1b' => (Key::Escape, KeyModifiers::NONE),
+        '\x01'..='\x1a' => (Key::Char((c as u8 - 1 + b'a') as char), KeyModifiers::CTRL),
+        c if c.is_ascii_uppercase() => (Key::Char(c.to_ascii_lowercase()), KeyModifiers::SHIFT),
+        c => (Key::Char(c), KeyModifiers::NONE)
+    }
+}
+
+/// Decode the utf-8 character at the start of the input along with its length in bytes, returns None if the input ends part way through it.
+/// Invalid bytes decode to no character with a length of 1 so they can be skipped
+fn decode_char(bytes: &[u8]) -> Option<(Option<char>, usize)> {
+    let width = match bytes[0] {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        _ => 4
+    };
+
+    match std::str::from_utf8(&bytes[..width.min(bytes.len())]) {
+        Ok(s) => Some((s.chars().next(), width)),
+        Err(e) if e.error_len().is_none() => None,
+        Err(_) => Some((None, 1))
+    }
+}
+
+/// Parse raw terminal input into events, returns the events and the number of bytes consumed. Incomplete sequences at the end of the input are left unconsumed, except for a lone escape which is reported as the escape key
+pub fn parse_input(bytes: &[u8]) -> (Vec<InputEvent>, usize) {
+    let mut events = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == 0x1b {
+            match bytes.get(i + 1) {
+                None => {
+                    events.push(InputEvent::key(Key::Escape, KeyModifiers::NONE));
+                    i += 1;
+                }
+                Some(b'[') => {
+                    // Parameters are digits separated by semicolons, terminated by a byte in the range 0x40 to 0x7e
+                    let Some(end) = bytes[i + 2..].iter().position(|b| (0x40..=0x7e).contains(b)) else { break };
+                    let end = i + 2 + end;
+
+                    let parameters: Vec<Option<u32>> = std::str::from_utf8(&bytes[i + 2..end]).unwrap_or("")
+                        .split(';')
+                        .map(|p| p.parse().ok())
+                        .collect();
+
+                    if let Some((key, modifiers)) = csi_key(&parameters, bytes[end]) {
+                        events.push(InputEvent::key(key, modifiers));
+                    }
+                    i = end + 1;
+                }
+                Some(b'O') => {
+                    let Some(final_byte) = bytes.get(i + 2) else { break };
+                    let key = match final_byte {
+                        b'P'..=b'S' => Some(Key::Function(final_byte - b'P' + 1)),
+                        _ => csi_key(&[], *final_byte).map(|(key, _)| key)
+                    };
+
+                    if let Some(key) = key {
+                        events.push(InputEvent::key(key, KeyModifiers::NONE));
+                    }
+                    i += 3;
+                }
+                Some(_) => match decode_char(&bytes[i + 1..]) {
+                    None => break,
+                    // Escape followed by another key is how terminals report alt
+                    Some((Some(c), width)) => {
+                        let (key, mut modifiers) = char_key(c);
+                        modifiers.alt = true;
+                        events.push(InputEvent::key(key, modifiers));
+                        i += 1 + width;
+                    }
+                    // The invalid byte is skipped on the next pass
+                    Some((None, _)) => {
+                        events.push(InputEvent::key(Key::Escape, KeyModifiers::NONE));
+                        i += 1;
+                    }
+                }
+            }
+        }
+        else {
+            let Some((c, width)) = decode_char(&bytes[i..]) else { break };
+
+            // Invalid utf-8 is skipped
+            if let Some(c) = c {
+                let (key, modifiers) = char_key(c);
+                events.push(InputEvent::key(key, modifiers));
+
+                if !c.is_control() {
+                    events.push(InputEvent::Text(c.to_string()));
+                }
+            }
+
+            i += width;
+        }
+    }
+
+    (events, i)
+}
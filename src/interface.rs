@@ -1,4 +1,12 @@
-use sdl2::{render::Canvas, EventPump};
+use sdl2::{render::Canvas, video::FullscreenType, EventPump};
+
+use std::time::Instant;
+
+use crate::app::{App, GameLoop, LoopSettings};
+use crate::character_map::{CharacterMap, GlyphLayout};
+use crate::input::{InputEvent, MouseTracker};
+use crate::scaling::{ScaleMode, Viewport};
+use crate::screen::TextBufferScreen;
 
 pub struct GameInterface {
     pub context: sdl2::Sdl,
@@ -8,11 +16,22 @@ pub struct GameInterface {
     pub timer_subsystem: sdl2::TimerSubsystem,
 
     pub event_pump: EventPump,
+
+    /// Converts mouse events to cells, update_viewport keeps it following the viewport
+    pub mouse: MouseTracker,
+
+    scale_mode: ScaleMode,
+    viewport: Option<Viewport>,
 }
 
 impl GameInterface {
     /// Construct a new GameInterface object using sdl2
     pub fn new(default_size: (usize, usize)) -> Result<Self, String> {
+        Self::with_vsync(default_size, false)
+    }
+
+    /// Construct a new GameInterface object, presenting frames in step with the display if vsync is set
+    pub fn with_vsync(default_size: (usize, usize), vsync: bool) -> Result<Self, String> {
         let sdl_context = sdl2::init()?;
         let video_subsystem = sdl_context.video()?;
 
@@ -22,12 +41,15 @@ impl GameInterface {
                 (default_size.1) as u32)
             .position_centered()
             .resizable()
+            .allow_highdpi()
             .build()
             .map_err(|e| e.to_string())?;
 
-        let mut canvas = window
-            .into_canvas()
-            .accelerated()
+        let mut canvas = window.into_canvas().accelerated();
+        if vsync {
+            canvas = canvas.present_vsync();
+        }
+        let mut canvas = canvas
             .build()
             .map_err(|e| e.to_string())?;
         canvas.set_draw_color(sdl2::pixels::Color::RGBA(0, 0, 0, 255));
@@ -40,7 +62,122 @@ impl GameInterface {
             canvas,
             video_subsystem,
             timer_subsystem,
-            event_pump
+            event_pump,
+            mouse: MouseTracker::default(),
+            scale_mode: ScaleMode::default(),
+            viewport: None
         })
     }
-}
\ No newline at end of file
+
+    pub fn scale_mode(&self) -> ScaleMode {
+        self.scale_mode
+    }
+
+    /// Change how the screen is fitted to the window, applied by the next call to update_viewport
+    pub fn set_scale_mode(&mut self, mode: ScaleMode) {
+        self.scale_mode = mode;
+        self.viewport = None;
+    }
+
+    /// Get the viewport set by the last call to update_viewport
+    pub fn viewport(&self) -> Option<Viewport> {
+        self.viewport
+    }
+
+    /// Get the size of the window in window pixels, which mouse positions are given in
+    pub fn window_size(&self) -> (u32, u32) {
+        self.canvas.window().size()
+    }
+
+    /// Get the size of the area drawn to in pixels, larger than the window size on high density displays
+    pub fn drawable_size(&self) -> Result<(u32, u32), String> {
+        self.canvas.output_size()
+    }
+
+    /// Get the percentage of drawable pixels to window pixels
+    pub fn hidpi_scale(&self) -> Result<usize, String> {
+        let window = self.window_size();
+        let drawable = self.drawable_size()?;
+        Ok((drawable.0 as usize * 100 / window.0.max(1) as usize).max(1))
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.canvas.window().fullscreen_state() != FullscreenType::Off
+    }
+
+    /// Switch between a window and borderless fullscreen at the desktop resolution
+    pub fn set_fullscreen(&mut self, fullscreen: bool) -> Result<(), String> {
+        let state = if fullscreen { FullscreenType::Desktop } else { FullscreenType::Off };
+        self.canvas.window_mut().set_fullscreen(state)
+    }
+
+    pub fn toggle_fullscreen(&mut self) -> Result<(), String> {
+        self.set_fullscreen(!self.is_fullscreen())
+    }
+
+    /// Fit the screen to the window with the scale mode, resizing the screen and pointing the mouse at the new viewport.
+    /// Call this whenever the window may have changed size, returns true if the screen was resized or moved
+    pub fn update_viewport(&mut self, screen: &mut TextBufferScreen, layout: &GlyphLayout) -> Result<bool, String> {
+        let hidpi_scale = self.hidpi_scale()?;
+        let viewport = self.scale_mode.viewport(self.drawable_size()?, hidpi_scale, layout);
+
+        if self.viewport == Some(viewport) && (screen.width(), screen.height()) == viewport.grid {
+            return Ok(false);
+        }
+
+        screen.resize_buffer(viewport.grid.0, viewport.grid.1);
+        screen.invalidate();
+        self.mouse.set_viewport(layout.character_size(), &viewport, hidpi_scale);
+        self.viewport = Some(viewport);
+
+        Ok(true)
+    }
+
+    /// Draw the changed cells of the screen to the window at the current viewport
+    pub fn present(&mut self, screen: &mut TextBufferScreen, character_map: &mut CharacterMap) -> Result<(), String> {
+        let viewport = self.viewport.unwrap_or(Viewport { grid: (screen.width(), screen.height()), pixel_scale: 100, origin: (0, 0) });
+        screen.write_to_viewport(&mut self.canvas, character_map, &viewport)
+    }
+
+    /// Get all of the pending window events which the engine understands as input events, with mouse positions in cells
+    pub fn poll_events(&mut self) -> Vec<InputEvent> {
+        let mouse = &mut self.mouse;
+        self.event_pump.poll_iter().filter_map(|event| InputEvent::from_sdl(&event).or_else(|| mouse.from_sdl(&event))).collect()
+    }
+
+    /// Run an app until it stops or the window is closed. The screen is fitted to the window with the scale mode,
+    /// and the loop sleeps between frames, waking early for input
+    pub fn run(&mut self, app: &mut (impl App + ?Sized), screen: &mut TextBufferScreen, character_map: &mut CharacterMap, settings: LoopSettings) -> Result<(), String> {
+        let mut game_loop = GameLoop::new(settings);
+        let mut events = Vec::new();
+
+        while game_loop.is_running() {
+            events.extend(self.poll_events());
+
+            if let Some(mode) = app.scale_mode().filter(|mode| *mode != self.scale_mode) {
+                self.set_scale_mode(mode);
+            }
+            if let Some(fullscreen) = app.fullscreen().filter(|fullscreen| *fullscreen != self.is_fullscreen()) {
+                self.set_fullscreen(fullscreen)?;
+            }
+            self.update_viewport(screen, character_map.layout())?;
+
+            let frame = game_loop.frame(app, screen, &events, Instant::now());
+            events.clear();
+
+            if frame.present {
+                self.present(screen, character_map)?;
+            }
+
+            let wait = frame.next_frame.saturating_duration_since(Instant::now());
+            if game_loop.is_running() && !wait.is_zero() {
+                let mouse = &mut self.mouse;
+                if let Some(event) = self.event_pump.wait_event_timeout(wait.as_millis().max(1) as u32) {
+                    events.extend(InputEvent::from_sdl(&event).or_else(|| mouse.from_sdl(&event)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,13 @@
+pub mod app;
+pub mod backend;
 pub mod character_map;
+pub mod formats;
+#[cfg(feature = "sdl")]
 pub mod interface;
+pub mod input;
+pub mod raster;
+pub mod scaling;
+pub mod scene;
 pub mod screen;
+pub mod snapshot;
 pub mod ui;
\ No newline at end of file
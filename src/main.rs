@@ -1,115 +1,142 @@
+use std::time::Duration;
+
+use asciiengine::app::{App, LoopSettings};
 use asciiengine::character_map::CharacterMap;
-use asciiengine::ui::{self, UIElement, MenuSettings};
+use asciiengine::input::{InputEvent, InputMap, Key, KeyChord, KeyModifiers, RepeatPolicy};
+use asciiengine::ui::{self, Constraint};
 
 use asciiengine::interface::GameInterface;
-use asciiengine::screen::{TextBufferScreen, TextAlign, TextBufferRect, TextView, TextBufferInterface, Drawable, CharacterColor};
-use sdl2::event::{Event, WindowEvent};
-use sdl2::keyboard::Keycode;
+use asciiengine::scaling::ScaleMode;
+use asciiengine::screen::{TextBufferScreen, TextAlign, TextBufferInterface};
 
 pub const INITIAL_SIZE: (usize, usize) = (640, 480);
+pub const CONTROLS_PATH: &str = "controls.cfg";
+
+struct Demo {
+    controls: InputMap,
+    layout: ui::ScreenLayout,
+    widgets: ui::FocusManager,
+    scroll_index: usize,
+    inventory_index: usize,
+    scale_mode: ScaleMode,
+    fullscreen: bool,
+    redraw_all: bool,
+    running: bool
+}
+
+impl Demo {
+    fn new() -> Result<Self, String> {
+        let mut controls = InputMap::new()
+            .bind("quit", Key::Escape)
+            .bind("zoom_out", Key::Char('u'))
+            .bind("zoom_in", Key::Char('j'))
+            .bind("scale_mode", Key::Char('m'))
+            .bind("fullscreen", Key::Function(11))
+            .bind("fullscreen", KeyChord::new(Key::Enter, KeyModifiers::ALT))
+            .repeat("zoom_out", RepeatPolicy::Repeat)
+            .repeat("zoom_in", RepeatPolicy::Repeat);
+        if std::path::Path::new(CONTROLS_PATH).exists() {
+            controls.load(CONTROLS_PATH)?;
+        }
 
-fn main() -> Result<(), String> {
-    let mut pixel_scale: usize = 100;
-    let mut interface = GameInterface::new(INITIAL_SIZE).unwrap();
-    let texture_creator = interface.canvas.texture_creator();
+        let inventory = ui::GraphicalMenu::new(vec!["I0", "Item1", "Itsdfgem2", "Item3", "Iteadsfm4", "Item5", "Item6", "Item7"], (8, 0).into(), 
+            ui::MenuSettings::new().horizontal(0).align(TextAlign::Center).fix_selection().hide_others().wrapping());
 
-    let mut character_map = CharacterMap::from_file("assets/codepage.bmp", &texture_creator, (9, 16), (8, 8), 32)?;
-    let mut text_buffer = TextBufferScreen::new(INITIAL_SIZE.0*100/9/pixel_scale, INITIAL_SIZE.1*100/16/pixel_scale);
-    let mut running = true;
-    
-    let mut i = 0;
+        let mut scrollbox = ui::ScrollBox::new((0, 0, 20, 20).into(), (50, 50));
 
-    let mut redraw_all = true;
+        for y in 0..50 {
+            scrollbox.write_string((0, y).into(), &format!("This is line number {}, it will contain many characters so as to show how scroll menus work", y), asciiengine::screen::CharacterColor::BrightWhite.into()).unwrap();
+        }
 
-    let mut inventory = ui::GraphicalMenu::new(vec!["I0", "Item1", "Itsdfgem2", "Item3", "Iteadsfm4", "Item5", "Item6", "Item7"], (8, 0).into(), 
-        ui::MenuSettings::new().horizontal(0).align(TextAlign::Center).fix_selection().hide_others().wrapping());
+        let layout = ui::ScreenLayout::new(ui::Layout::horizontal()
+            .split(Constraint::Percent(50), ui::Layout::vertical()
+                .pane(Constraint::Percent(75), "pane0")
+                .pane(Constraint::Fill(1), "pane1"))
+            .split(Constraint::Fill(1), ui::Layout::vertical()
+                .pane(Constraint::Percent(50), "pane2")
+                .pane(Constraint::Fill(1), "pane3")));
 
-    let mut scrollbox = ui::ScrollBox::new((0, 0, 20, 20).into(), (50, 50));
+        let mut widgets = ui::FocusManager::new();
+        let scroll_index = widgets.push_pane(scrollbox, (0, 0, 20, 20).into(), Some(("Pane2", TextAlign::Center)));
+        let inventory_index = widgets.push_pane(inventory, (0, 0, 20, 20).into(), Some(("Pane3", TextAlign::Center)));
 
-    for y in 0..50 {
-        scrollbox.write_string((0, y).into(), &format!("This is line number {}, it will contain many characters so as to show how scroll menus work", y), asciiengine::screen::CharacterColor::BrightWhite).unwrap();
+        Ok(Self { controls, layout, widgets, scroll_index, inventory_index, scale_mode: ScaleMode::default(), fullscreen: false, redraw_all: true, running: true })
     }
+}
 
-    while running {
-        for event in interface.event_pump.poll_iter() {
-            match event {
-                Event::MouseMotion { .. } => {},
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => {
-                    running = false;
-                }
-                Event::Window { win_event: WindowEvent::Resized(width, height), .. } => {
-                    let (cw, ch) = character_map.character_size();
-                    text_buffer.resize_buffer(width as usize * 100 / pixel_scale / cw, height as usize * 100 / pixel_scale / ch);
-                    redraw_all = true;
-                }
-
-                Event::KeyDown { keycode: Some(Keycode::U), .. } => {
-                    let width = INITIAL_SIZE.0;
-                    let height = INITIAL_SIZE.1;
-                    pixel_scale -= 10;
-                    println!("{}", pixel_scale);
-                    let (cw, ch) = character_map.character_size();
-                    text_buffer.resize_buffer(width as usize * 100 / pixel_scale / cw, height as usize * 100 / pixel_scale / ch);
-                    redraw_all = true;
-                 }
-                Event::KeyDown { keycode: Some(Keycode::J), .. } => { 
-                    let width = INITIAL_SIZE.0;
-                    let height = INITIAL_SIZE.1;
-                    pixel_scale += 10;
-                    println!("{}", pixel_scale);
-                    let (cw, ch) = character_map.character_size();
-                    text_buffer.resize_buffer(width as usize * 100 / pixel_scale / cw, height as usize * 100 / pixel_scale / ch);
-                    redraw_all = true;
-                }
-
-                Event::KeyDown { keycode: Some(Keycode::A), .. } => { inventory.prev() }
-                Event::KeyDown { keycode: Some(Keycode::D), .. } => { inventory.next() }
-                // Event::KeyDown { keycode: Some(Keycode::A), .. } => { i -= 1; redraw_all = true; }
-                // Event::KeyDown { keycode: Some(Keycode::D), .. } => { i += 1; redraw_all = true; }
-
-                Event::KeyDown { keycode: Some(Keycode::Left), .. } => { scrollbox.scroll_horizontal(-1) }
-                Event::KeyDown { keycode: Some(Keycode::Right), .. } => { scrollbox.scroll_horizontal(1) }
-                Event::KeyDown { keycode: Some(Keycode::Up), .. } => { scrollbox.scroll_vertical(-1) }
-                Event::KeyDown { keycode: Some(Keycode::Down), .. } => { scrollbox.scroll_vertical(1) }
-                _ => {}
-            }
+impl App for Demo {
+    fn handle_event(&mut self, event: &InputEvent) {
+        if let InputEvent::Resize(..) = event {
+            self.redraw_all = true;
+        }
+        else if !self.controls.handle_event(event) {
+            self.widgets.handle_event(event);
         }
+    }
 
-        let width = text_buffer.width();
-        let height = text_buffer.height();
+    fn update(&mut self, _dt: Duration) {
+        if self.controls.pressed("quit") {
+            self.running = false;
+        }
 
-        let pane0: TextBufferRect = (0, 0, (width as isize / 2 + i) as usize, height * 3 / 4).into();
-        let pane1: TextBufferRect = (0, height as isize * 3 / 4, (width as isize / 2 + i) as usize, (height + 3) / 4).into();
-        let pane2: TextBufferRect = ((width as isize) / 2 + i, 0, ((width + 1) as isize / 2 - i) as usize, height /2 ).into();
-        let pane3: TextBufferRect = ((width as isize) / 2 + i, height as isize / 2, ((width + 1) as isize / 2 - i) as usize, (height + 1) / 2).into();
+        if let ScaleMode::Fill { pixel_scale } = &mut self.scale_mode {
+            if self.controls.pressed("zoom_in") {
+                *pixel_scale += 10;
+            }
+            if self.controls.pressed("zoom_out") {
+                *pixel_scale = pixel_scale.saturating_sub(10).max(10);
+            }
+        }
+
+        if self.controls.pressed("scale_mode") {
+            self.scale_mode = match self.scale_mode {
+                ScaleMode::Fill { .. } => ScaleMode::IntegerFit { columns: 80, rows: 25 },
+                ScaleMode::IntegerFit { columns, rows } => ScaleMode::FixedGrid { columns, rows },
+                ScaleMode::FixedGrid { .. } => ScaleMode::default()
+            };
+        }
 
-        if redraw_all {
-            text_buffer.clear_rect(text_buffer.screen_rect());
-            ui::draw_box(pane0, &mut text_buffer, Some(("Pane0 A Stupidly Long Name", TextAlign::Right)));
-            ui::draw_box(pane1, &mut text_buffer, Some(("Pane1", TextAlign::Center)));
-            ui::draw_box(pane2, &mut text_buffer, Some(("Pane2", TextAlign::Center)));
-            ui::draw_box(pane3, &mut text_buffer, Some(("Pane3", TextAlign::Center)));
+        if self.controls.pressed("fullscreen") {
+            self.fullscreen = !self.fullscreen;
         }
 
-        if redraw_all || inventory.take_dirty() || scrollbox.take_dirty() {
-            let mut view = TextView::new(pane2.interior(), &mut text_buffer);
-            scrollbox.rect.width = pane2.interior().width;
-            scrollbox.rect.height = pane2.interior().height;
-            scrollbox.ui_draw(&mut view);
-
-            
-            let mut view = TextView::new(pane3.interior(), &mut text_buffer);
-            inventory.clear_last(&mut view);
-            inventory.ui_draw(&mut view);
-            redraw_all = false;
+        self.controls.next_frame();
+    }
+
+    fn render(&mut self, screen: &mut TextBufferScreen) {
+        if self.layout.update(screen) || self.redraw_all {
+            screen.clear_rect(screen.screen_rect());
+            ui::draw_box(self.layout["pane0"], screen, Some(("Pane0 A Stupidly Long Name", TextAlign::Right)));
+            ui::draw_box(self.layout["pane1"], screen, Some(("Pane1", TextAlign::Center)));
+
+            self.widgets.set_pane_rect(self.scroll_index, self.layout["pane2"]);
+            self.widgets.set_pane_rect(self.inventory_index, self.layout["pane3"]);
+            self.redraw_all = false;
         }
-        
-        text_buffer.write_to_canvas(&mut interface.canvas, &mut character_map, pixel_scale)?;
+
+        self.widgets.draw(screen);
+    }
+
+    fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn scale_mode(&self) -> Option<ScaleMode> {
+        Some(self.scale_mode)
     }
 
-    Ok(())
-}
\ No newline at end of file
+    fn fullscreen(&self) -> Option<bool> {
+        Some(self.fullscreen)
+    }
+}
+
+fn main() -> Result<(), String> {
+    let mut interface = GameInterface::new(INITIAL_SIZE)?;
+    let texture_creator = interface.canvas.texture_creator();
+
+    let mut character_map = CharacterMap::from_file("assets/codepage.bmp", &texture_creator, (9, 16), (8, 8), 32)?;
+    let mut text_buffer = TextBufferScreen::new(INITIAL_SIZE.0 / 9, INITIAL_SIZE.1 / 16);
+    let mut demo = Demo::new()?;
+
+    interface.run(&mut demo, &mut text_buffer, &mut character_map, LoopSettings::new())
+}
@@ -0,0 +1,295 @@
+use std::io::Write;
+use std::path::Path;
+
+use crate::character_map::GlyphLayout;
+
+/// An image stored as 8-bit RGBA pixels in row major order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    width: usize,
+    height: usize,
+    data: Vec<u8>
+}
+
+impl RgbaImage {
+    /// Construct a new image filled with a single color
+    pub fn new(width: usize, height: usize, fill: (u8, u8, u8, u8)) -> Self {
+        Self {
+            width,
+            height,
+            data: [fill.0, fill.1, fill.2, fill.3].repeat(width * height)
+        }
+    }
+
+    /// Get the width of the image
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the image
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the raw RGBA data of the image
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the pixel at the given position
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<(u8, u8, u8, u8)> {
+        if x < self.width && y < self.height {
+            let i = (x + y * self.width) * 4;
+            Some((self.data[i], self.data[i + 1], self.data[i + 2], self.data[i + 3]))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Set the pixel at the given position, positions outside of the image are ignored
+    pub fn set_pixel(&mut self, x: isize, y: isize, color: (u8, u8, u8, u8)) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            let i = (x as usize + y as usize * self.width) * 4;
+            self.data[i..i + 4].copy_from_slice(&[color.0, color.1, color.2, color.3]);
+        }
+    }
+
+    /// Fill a rectangle given as (x, y, width, height), clipped to the image
+    pub fn fill_rect(&mut self, rect: (isize, isize, usize, usize), color: (u8, u8, u8, u8)) {
+        for y in rect.1..rect.1 + rect.3 as isize {
+            for x in rect.0..rect.0 + rect.2 as isize {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Load an uncompressed 8, 24 or 32 bit BMP file
+    pub fn load_bmp(path: impl AsRef<Path>) -> Result<Self, String> {
+        Self::decode_bmp(&std::fs::read(path).map_err(|e| e.to_string())?)
+    }
+
+    /// Decode an uncompressed 8, 24 or 32 bit BMP image
+    pub fn decode_bmp(bytes: &[u8]) -> Result<Self, String> {
+        let u16_at = |i: usize| bytes.get(i..i + 2).map(|b| u16::from_le_bytes([b[0], b[1]])).ok_or("Truncated BMP header");
+        let u32_at = |i: usize| bytes.get(i..i + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).ok_or("Truncated BMP header");
+
+        if bytes.get(0..2) != Some(b"BM") {
+            return Err("Not a BMP file".to_string());
+        }
+
+        let pixel_offset = u32_at(10)? as usize;
+        let header_size = u32_at(14)? as usize;
+        let width = u32_at(18)? as i32;
+        let height = u32_at(22)? as i32;
+        let bits = u16_at(28)?;
+        let compression = u32_at(30)?;
+
+        // Bitfields are accepted for 32 bit images as long as they are the usual BGRA order
+        if compression != 0 && !(compression == 3 && bits == 32) {
+            return Err(format!("Unsupported BMP compression {}", compression));
+        }
+
+        if !matches!(bits, 8 | 24 | 32) {
+            return Err(format!("Unsupported BMP bit depth {}", bits));
+        }
+
+        let width = width.unsigned_abs() as usize;
+        let top_down = height < 0;
+        let height = height.unsigned_abs() as usize;
+
+        let palette: Vec<(u8, u8, u8)> = if bits == 8 {
+            let count = match u32_at(46)? {
+                0 => 256,
+                n => n as usize
+            };
+
+            (0..count).map(|i| {
+                let entry = 14 + header_size + i * 4;
+                bytes.get(entry..entry + 3).map(|b| (b[2], b[1], b[0])).ok_or("Truncated BMP palette")
+            }).collect::<Result<_, _>>()?
+        }
+        else {
+            Vec::new()
+        };
+
+        // The header is checked against the pixel data before anything is allocated from it
+        let stride = width.checked_mul(bits as usize).map(|row_bits| row_bits.div_ceil(32) * 4);
+        let data_end = stride.and_then(|stride| stride.checked_mul(height)).and_then(|length| length.checked_add(pixel_offset));
+        let (Some(stride), Some(data_end)) = (stride, data_end) else {
+            return Err("BMP image is too large".to_string());
+        };
+        if data_end > bytes.len() {
+            return Err("Truncated BMP pixel data".to_string());
+        }
+
+        let mut image = Self::new(width, height, (0, 0, 0, 255));
+
+        for row in 0..height {
+            let y = if top_down { row } else { height - 1 - row };
+            let start = pixel_offset + row * stride;
+            let line = &bytes[start..start + stride];
+
+            for x in 0..width {
+                let color = match bits {
+                    8 => {
+                        let (r, g, b) = palette.get(line[x] as usize).copied().unwrap_or((0, 0, 0));
+                        (r, g, b, 255)
+                    }
+                    24 => (line[x * 3 + 2], line[x * 3 + 1], line[x * 3], 255),
+                    _ => (line[x * 4 + 2], line[x * 4 + 1], line[x * 4], 255)
+                };
+
+                image.set_pixel(x as isize, y as isize, color);
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Encode the image as a 32 bit BMP
+    pub fn encode_bmp(&self) -> Vec<u8> {
+        let pixel_bytes = self.width * self.height * 4;
+        let mut result = Vec::with_capacity(54 + pixel_bytes);
+
+        // File header
+        result.extend_from_slice(b"BM");
+        result.extend_from_slice(&(54 + pixel_bytes as u32).to_le_bytes());
+        result.extend_from_slice(&[0; 4]);
+        result.extend_from_slice(&54u32.to_le_bytes());
+
+        // Info header, a negative height stores the rows top down
+        result.extend_from_slice(&40u32.to_le_bytes());
+        result.extend_from_slice(&(self.width as i32).to_le_bytes());
+        result.extend_from_slice(&(-(self.height as i32)).to_le_bytes());
+        result.extend_from_slice(&1u16.to_le_bytes());
+        result.extend_from_slice(&32u16.to_le_bytes());
+        result.extend_from_slice(&0u32.to_le_bytes());
+        result.extend_from_slice(&(pixel_bytes as u32).to_le_bytes());
+        result.extend_from_slice(&2835u32.to_le_bytes());
+        result.extend_from_slice(&2835u32.to_le_bytes());
+        result.extend_from_slice(&[0; 8]);
+
+        for pixel in self.data.chunks_exact(4) {
+            result.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+        }
+
+        result
+    }
+
+    /// Encode the image as an RGBA PNG, PNG can not store images without any pixels so empty images are an error
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!("Can not encode a {}x{} image as a PNG", self.width, self.height));
+        }
+
+        let mut result = b"\x89PNG\r\n\x1a\n".to_vec();
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+        write_png_chunk(&mut result, b"IHDR", &header);
+
+        // Every scanline is stored unfiltered
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        for row in self.data.chunks_exact(self.width * 4) {
+            encoder.write_all(&[0]).map_err(|e| e.to_string())?;
+            encoder.write_all(row).map_err(|e| e.to_string())?;
+        }
+        let compressed = encoder.finish().map_err(|e| e.to_string())?;
+        write_png_chunk(&mut result, b"IDAT", &compressed);
+
+        write_png_chunk(&mut result, b"IEND", &[]);
+
+        Ok(result)
+    }
+
+    /// Write the image to a BMP file
+    pub fn save_bmp(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.encode_bmp()).map_err(|e| e.to_string())
+    }
+
+    /// Write the image to a PNG file
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        std::fs::write(path, self.encode_png()?).map_err(|e| e.to_string())
+    }
+
+    /// Write the image to a file, choosing the format from the extension
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("png") => self.save_png(path),
+            Some("bmp") => self.save_bmp(path),
+            _ => Err(format!("Unknown image format for {}", path.display()))
+        }
+    }
+}
+
+/// Compute the CRC32 used by PNG chunks
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+/// Append a PNG chunk with its length and checksum
+fn write_png_chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = output.len();
+    output.extend_from_slice(kind);
+    output.extend_from_slice(data);
+    let crc = crc32(&output[start..]);
+
+    output.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Codepage glyphs loaded into memory for rendering without a window
+#[derive(Debug, Clone)]
+pub struct SoftwareFont {
+    image: RgbaImage,
+    layout: GlyphLayout
+}
+
+impl SoftwareFont {
+    /// Construct a new font from an image of the glyphs
+    pub const fn new(image: RgbaImage, layout: GlyphLayout) -> Self {
+        Self {
+            image,
+            layout
+        }
+    }
+
+    /// Construct a new font from a BMP file, taking the same arguments as `CharacterMap::from_file`
+    pub fn from_file(path: impl AsRef<Path>, character_size: (usize, usize), start_pos: (usize, usize), per_row: usize) -> Result<Self, String> {
+        Ok(Self::new(RgbaImage::load_bmp(path)?, GlyphLayout::new(character_size, start_pos, per_row)))
+    }
+
+    /// Get the layout of the glyphs in the font
+    pub const fn layout(&self) -> &GlyphLayout {
+        &self.layout
+    }
+
+    /// Get the pixel of a glyph at the given offset, black pixels are transparent and return None
+    pub fn glyph_pixel(&self, character_index: u8, x: usize, y: usize) -> Option<(u8, u8, u8)> {
+        let (gx, gy, width, height) = self.layout.get_rect(character_index);
+
+        if x >= width || y >= height {
+            return None;
+        }
+
+        match self.image.get_pixel(gx as usize + x, gy as usize + y)? {
+            (0, 0, 0, _) => None,
+            (r, g, b, _) => Some((r, g, b))
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::character_map::GlyphLayout;
+
+/// How a grid of cells is fitted to a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Keep the grid size and draw it at the largest whole multiple of the glyph size which fits, so glyphs stay crisp. The space around it is letterboxed
+    IntegerFit { columns: usize, rows: usize },
+    /// Keep the grid size and stretch it as large as fits while keeping its shape, letterboxing the rest. Glyphs may be unevenly sized
+    FixedGrid { columns: usize, rows: usize },
+    /// Draw glyphs at a set percentage of their size, a larger window shows more cells
+    Fill { pixel_scale: usize }
+}
+
+impl std::default::Default for ScaleMode {
+    fn default() -> Self {
+        ScaleMode::Fill { pixel_scale: 100 }
+    }
+}
+
+/// Where a grid of cells is drawn in a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    /// Size of the text buffer in cells
+    pub grid: (usize, usize),
+    /// Percentage of the glyph size cells are drawn at, in drawable pixels
+    pub pixel_scale: usize,
+    /// Top left corner of the grid in drawable pixels
+    pub origin: (i32, i32)
+}
+
+impl ScaleMode {
+    /// Work out the viewport for a drawable area in pixels. The hidpi scale is the percentage of drawable pixels to window pixels,
+    /// which fill mode uses so cells keep the same size on screen on high density displays
+    pub fn viewport(&self, drawable: (u32, u32), hidpi_scale: usize, layout: &GlyphLayout) -> Viewport {
+        let (cw, ch) = layout.character_size();
+        let (width, height) = (drawable.0 as usize, drawable.1 as usize);
+
+        let (grid, pixel_scale) = match *self {
+            ScaleMode::IntegerFit { columns, rows } => {
+                let fit = (width / (columns * cw).max(1)).min(height / (rows * ch).max(1)).max(1);
+                ((columns, rows), fit * 100)
+            }
+            ScaleMode::FixedGrid { columns, rows } => {
+                let fit = (width * 100 / (columns * cw).max(1)).min(height * 100 / (rows * ch).max(1)).max(1);
+                ((columns, rows), fit)
+            }
+            ScaleMode::Fill { pixel_scale } => {
+                let scale = (pixel_scale * hidpi_scale / 100).max(1);
+                ((width * 100 / (cw * scale).max(1), height * 100 / (ch * scale).max(1)), scale)
+            }
+        };
+
+        let size = layout.grid_size(grid.0, grid.1, pixel_scale);
+        let origin = match self {
+            ScaleMode::Fill { .. } => (0, 0),
+            _ => ((width.saturating_sub(size.0) / 2) as i32, (height.saturating_sub(size.1) / 2) as i32)
+        };
+
+        Viewport { grid, pixel_scale, origin }
+    }
+}
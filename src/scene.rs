@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use crate::app::App;
+use crate::input::InputEvent;
+use crate::screen::{CellAttributes, TextBufferInterface, TextBufferScreen};
+
+/// What is shown under a scene
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backdrop {
+    /// The scene covers the whole screen and the scenes below it are not drawn
+    #[default]
+    Hidden,
+    /// The scenes below keep drawing underneath, for popups and heads up displays
+    Shown,
+    /// The scenes below keep drawing underneath in dim colors, for pause screens and modal dialogs
+    Dimmed
+}
+
+/// A change to the scene stack requested by the top scene
+pub enum Transition {
+    None,
+    /// Cover the current scene with a new one
+    Push(Box<dyn Scene>),
+    /// Remove the current scene, returning to the one below it
+    Pop,
+    /// Swap the current scene for a new one
+    Replace(Box<dyn Scene>),
+    /// Remove every scene, which stops the app
+    Quit
+}
+
+impl Transition {
+    pub fn push(scene: impl Scene + 'static) -> Self {
+        Transition::Push(Box::new(scene))
+    }
+
+    pub fn replace(scene: impl Scene + 'static) -> Self {
+        Transition::Replace(Box::new(scene))
+    }
+}
+
+/// A screen of a game such as the title, gameplay, inventory or pause screen. Scenes own their widgets and only the top scene receives input and updates
+pub trait Scene {
+    /// Handle an input event while the scene is on top
+    fn handle_event(&mut self, _event: &InputEvent) -> Transition {
+        Transition::None
+    }
+
+    /// Advance the scene by one tick while it is on top
+    fn update(&mut self, _dt: Duration) -> Transition {
+        Transition::None
+    }
+
+    /// Draw the parts of the scene which changed, or everything after invalidate was called
+    fn render(&mut self, screen: &mut TextBufferScreen);
+
+    /// Draw everything on the next render, called when the screen was cleared or a scene below drew over this one
+    fn invalidate(&mut self);
+
+    fn backdrop(&self) -> Backdrop {
+        Backdrop::Hidden
+    }
+
+    /// Called when the scene is added to the stack
+    fn on_enter(&mut self) {}
+
+    /// Called when the scene is removed from the stack
+    fn on_exit(&mut self) {}
+
+    /// Called when another scene is pushed on top of this one
+    fn on_pause(&mut self) {}
+
+    /// Called when this scene is back on top after the scene covering it was removed
+    fn on_resume(&mut self) {}
+}
+
+/// A stack of scenes drawn from the bottom up, starting at the highest scene with a hidden backdrop.
+/// Lower scenes which change cause the scenes above them to redraw, so only changed cells reach the screen's dirty regions
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    redraw_all: bool
+}
+
+impl SceneStack {
+    pub fn new(scene: impl Scene + 'static) -> Self {
+        let mut stack = Self { scenes: Vec::new(), redraw_all: true };
+        stack.push(scene);
+        stack
+    }
+
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Check if every scene has been removed
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Get the scene receiving input
+    pub fn top(&self) -> Option<&dyn Scene> {
+        self.scenes.last().map(|s| s.as_ref())
+    }
+
+    pub fn top_mut(&mut self) -> Option<&mut (dyn Scene + 'static)> {
+        self.scenes.last_mut().map(|s| s.as_mut())
+    }
+
+    pub fn push(&mut self, scene: impl Scene + 'static) {
+        self.apply(Transition::push(scene));
+    }
+
+    pub fn pop(&mut self) -> Option<Box<dyn Scene>> {
+        let mut scene = self.scenes.pop()?;
+        scene.on_exit();
+
+        if let Some(top) = self.scenes.last_mut() {
+            top.on_resume();
+        }
+
+        self.redraw_all = true;
+        Some(scene)
+    }
+
+    /// Apply a transition requested by a scene
+    pub fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::None => return,
+            Transition::Push(mut scene) => {
+                if let Some(top) = self.scenes.last_mut() {
+                    top.on_pause();
+                }
+                scene.on_enter();
+                self.scenes.push(scene);
+            }
+            Transition::Pop => {
+                self.pop();
+            }
+            Transition::Replace(mut scene) => {
+                if let Some(mut old) = self.scenes.pop() {
+                    old.on_exit();
+                }
+                scene.on_enter();
+                self.scenes.push(scene);
+            }
+            Transition::Quit => {
+                for mut scene in self.scenes.drain(..).rev() {
+                    scene.on_exit();
+                }
+            }
+        }
+
+        self.redraw_all = true;
+    }
+
+    /// Clear the screen and redraw every visible scene on the next render
+    pub fn invalidate(&mut self) {
+        self.redraw_all = true;
+    }
+
+    /// Give an input event to the top scene
+    pub fn handle_event(&mut self, event: &InputEvent) {
+        if let Some(top) = self.scenes.last_mut() {
+            let transition = top.handle_event(event);
+            self.apply(transition);
+        }
+    }
+
+    /// Update the top scene
+    pub fn update(&mut self, dt: Duration) {
+        if let Some(top) = self.scenes.last_mut() {
+            let transition = top.update(dt);
+            self.apply(transition);
+        }
+    }
+
+    /// Draw the visible scenes from the bottom up
+    pub fn render(&mut self, screen: &mut TextBufferScreen) {
+        let Some(mut first) = self.scenes.len().checked_sub(1) else {
+            return;
+        };
+        while first > 0 && self.scenes[first].backdrop() != Backdrop::Hidden {
+            first -= 1;
+        }
+
+        let mut changed = self.redraw_all;
+        if self.redraw_all {
+            screen.clear_rect(screen.screen_rect());
+            for scene in self.scenes[first..].iter_mut() {
+                scene.invalidate();
+            }
+            self.redraw_all = false;
+        }
+
+        for index in first..self.scenes.len() {
+            let before = screen.dirty_regions().to_vec();
+            self.scenes[index].render(screen);
+            changed |= screen.dirty_regions() != before;
+
+            if !changed || index + 1 == self.scenes.len() {
+                continue;
+            }
+
+            // Dimming is kept on cells which are already dim, so everything changed this frame can be dimmed again
+            let above = &mut self.scenes[index + 1..];
+            if above.iter().any(|scene| scene.backdrop() == Backdrop::Dimmed) {
+                for rect in screen.dirty_regions().to_vec() {
+                    screen.add_attributes_rect(rect, CellAttributes::DIM);
+                }
+            }
+            for scene in above.iter_mut() {
+                scene.invalidate();
+            }
+        }
+    }
+}
+
+impl App for SceneStack {
+    fn handle_event(&mut self, event: &InputEvent) {
+        self.handle_event(event);
+    }
+
+    fn update(&mut self, dt: Duration) {
+        self.update(dt);
+    }
+
+    fn render(&mut self, screen: &mut TextBufferScreen) {
+        self.render(screen);
+    }
+
+    fn is_running(&self) -> bool {
+        !self.is_empty()
+    }
+}
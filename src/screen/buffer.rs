@@ -1,8 +1,6 @@
 use super::*;
 
-use sdl2::render::Canvas;
-
-use crate::character_map::CharacterMap;
+use crate::backend::{RenderBackend, RenderContext};
 
 /// Trait to allow generic usage of text views and the buffer display
 pub trait TextBufferInterface {
@@ -18,36 +16,59 @@ pub trait TextBufferInterface {
     fn index_of(&self, pos: TextBufferPos) -> Option<usize>;
 
     /// Get access to the inner data buffer (if in a text view, this is the one at the lowest level)
-    fn inner_data_buffer(&self) -> &[(VGAChar, CharacterColor)];
+    fn inner_data_buffer(&self) -> &[Cell];
 
     /// Get access to the inner data buffer (if in a text view, this is the one at the lowest level)
-    fn inner_data_buffer_mut(&mut self) -> &mut [(VGAChar, CharacterColor)];
+    fn inner_data_buffer_mut(&mut self) -> &mut [Cell];
 
     /// Get a mutable reference to the character at the given position
-    fn inner_mut_char(&mut self, pos: TextBufferPos) -> Option<&mut (VGAChar, CharacterColor)> {
+    fn inner_mut_char(&mut self, pos: TextBufferPos) -> Option<&mut Cell> {
         let i = self.index_of(pos)?;
         Some(&mut self.inner_data_buffer_mut()[i])
     }
 
     /// Get a mutable reference to the character at the given position, try to avoid using this and prefer the instructions which write larger sections of the text buffer at once
-    fn mut_char(&mut self, pos: TextBufferPos) -> Option<&mut (VGAChar, CharacterColor)> {
+    fn mut_char(&mut self, pos: TextBufferPos) -> Option<&mut Cell> {
         self.add_dirty_rect(TextBufferRect::new(pos.x, pos.y, 1, 1));
         let i = self.index_of(pos)?;
         Some(&mut self.inner_data_buffer_mut()[i])
     }
 
     /// Get the character at the given position
-    fn char_ref(&self, pos: TextBufferPos) -> Option<&(VGAChar, CharacterColor)> {
+    fn char_ref(&self, pos: TextBufferPos) -> Option<&Cell> {
         let i = self.index_of(pos)?;
         Some(&self.inner_data_buffer()[i])
     }
 
+    /// Fill a rectangle with copies of the given cell
+    fn fill_rect(&mut self, rect: TextBufferRect, cell: Cell) {
+        for x in rect.x..rect.right() {
+            for y in rect.y..rect.bottom() {
+                if let Some(c) = self.inner_mut_char(TextBufferPos{x, y}) {
+                    *c = cell;
+                }
+            }
+        }
+
+        self.add_dirty_rect(rect);
+    }
+
     /// Clear a rectangle
     fn clear_rect(&mut self, rect: TextBufferRect) {
+        self.fill_rect(rect, Cell::blank());
+    }
+
+    /// Clear a rectangle to the given background color
+    fn clear_rect_background(&mut self, rect: TextBufferRect, background: Color) {
+        self.fill_rect(rect, Cell::new(VGAChar(b' '), CellStyle::default().background(background)));
+    }
+
+    /// Add display attributes to every cell in a rectangle, keeping their characters and colors
+    fn add_attributes_rect(&mut self, rect: TextBufferRect, attributes: CellAttributes) {
         for x in rect.x..rect.right() {
             for y in rect.y..rect.bottom() {
                 if let Some(c) = self.inner_mut_char(TextBufferPos{x, y}) {
-                    *c = (VGAChar(b' '), CharacterColor::Gray);
+                    c.style.attributes |= attributes;
                 }
             }
         }
@@ -55,11 +76,11 @@ pub trait TextBufferInterface {
         self.add_dirty_rect(rect);
     }
 
-    /// Write text to the display at a specific location, takes in a slice of VGAChar's and a color for the text
-    fn write_text(&mut self, pos: TextBufferPos, text: &[VGAChar], color: CharacterColor) -> TextBufferRect {
+    /// Write text to the display at a specific location, takes in a slice of VGAChar's and a style for the text
+    fn write_text(&mut self, pos: TextBufferPos, text: &[VGAChar], style: CellStyle) -> TextBufferRect {
         for (i, c) in text.iter().enumerate() {
             if let Some(cref) = self.inner_mut_char(TextBufferPos{x: pos.x + i as isize, y: pos.y}) {
-                *cref = (*c, color);
+                *cref = Cell::new(*c, style);
             }
         }
 
@@ -68,16 +89,16 @@ pub trait TextBufferInterface {
         rect
     }
 
-    /// Write text to the display at a specific location respecting text alignment, takes in a slice of VGAChar's and a color for the text
-    fn write_text_align(&mut self, pos: TextBufferPos, text: &[VGAChar], color: CharacterColor, align: TextAlign) -> TextBufferRect {
-        self.write_text(align.align_text(pos, text.len()), text, color)
+    /// Write text to the display at a specific location respecting text alignment, takes in a slice of VGAChar's and a style for the text
+    fn write_text_align(&mut self, pos: TextBufferPos, text: &[VGAChar], style: CellStyle, align: TextAlign) -> TextBufferRect {
+        self.write_text(align.align_text(pos, text.len()), text, style)
     }
 
     /// Write a string to the display at a specific location. Takes in an &str instead of a slice of VGAChar's. This function attempts to perform the conversion to VGAChar's, returning an error if the conversion cannot be performed
-    fn write_string(&mut self, pos: TextBufferPos, text: &str, color: CharacterColor) -> Result<TextBufferRect, char> {
+    fn write_string(&mut self, pos: TextBufferPos, text: &str, style: CellStyle) -> Result<TextBufferRect, char> {
         for (i, c) in text.chars().enumerate() {
             if let Some(cref) = self.inner_mut_char(TextBufferPos{x: pos.x + i as isize, y: pos.y}) {
-                *cref = (c.try_into()?, color);
+                *cref = Cell::new(c.try_into()?, style);
             }
         }
 
@@ -88,12 +109,12 @@ pub trait TextBufferInterface {
     }
 
     /// Write a string to the display at a specific location respecting text alignment. Takes in an &str instead of a slice of VGAChar's. This function attempts to perform the conversion to VGAChar's, returning an error if the conversion cannot be performed
-    fn write_string_align(&mut self, pos: TextBufferPos, text: &str, color: CharacterColor, align: TextAlign) -> Result<TextBufferRect, char> {
-        self.write_string(align.align_text(pos, text.len()), text, color)
+    fn write_string_align(&mut self, pos: TextBufferPos, text: &str, style: CellStyle, align: TextAlign) -> Result<TextBufferRect, char> {
+        self.write_string(align.align_text(pos, text.len()), text, style)
     }
 
-    /// Write a line of pre colored text to the display at the given position, takes in a slice of (VGAChar, CharacterColor) pairs. This is preferable for staticly allocated text
-    fn write_data(&mut self, pos: TextBufferPos, text: &[(VGAChar, CharacterColor)]) -> TextBufferRect {
+    /// Write a line of pre colored text to the display at the given position, takes in a slice of Cells. This is preferable for staticly allocated text
+    fn write_data(&mut self, pos: TextBufferPos, text: &[Cell]) -> TextBufferRect {
         for (i, c) in text.iter().enumerate() {
             if let Some(cref) = self.inner_mut_char(TextBufferPos{x: pos.x + i as isize, y: pos.y}) {
                 *cref = *c;
@@ -106,8 +127,8 @@ pub trait TextBufferInterface {
         rect
     }
 
-    /// Write a line of pre colored text to the display at the given position respecting text alignment, takes in a slice of (VGAChar, CharacterColor) pairs. This is preferable for staticly allocated text
-    fn write_data_align(&mut self, pos: TextBufferPos, text: &[(VGAChar, CharacterColor)], align: TextAlign) -> TextBufferRect {
+    /// Write a line of pre colored text to the display at the given position respecting text alignment, takes in a slice of Cells. This is preferable for staticly allocated text
+    fn write_data_align(&mut self, pos: TextBufferPos, text: &[Cell], align: TextAlign) -> TextBufferRect {
         self.write_data(align.align_text(pos, text.len()), text)
     }
 
@@ -123,9 +144,11 @@ pub trait TextBufferInterface {
 pub struct TextBufferScreen {
     width: usize,
     height: usize, 
-    data: Vec<(VGAChar, CharacterColor)>,
+    data: Vec<Cell>,
     dirty_regions: Vec<TextBufferRect>,
     clear_optimization_heuristic: bool,
+    last_blink_visible: bool,
+    palette: Palette,
 }
 
 impl TextBufferScreen {
@@ -134,9 +157,11 @@ impl TextBufferScreen {
         Self {
             width,
             height,
-            data: vec![(VGAChar(b' '), CharacterColor::Gray); width * height],
+            data: vec![Cell::blank(); width * height],
             dirty_regions: vec![TextBufferRect::new(0, 0, width, height)],
-            clear_optimization_heuristic: true
+            clear_optimization_heuristic: true,
+            last_blink_visible: true,
+            palette: Palette::default()
         }
     }
 
@@ -171,30 +196,30 @@ impl TextBufferScreen {
     }
 
     /// Get a mutable reference to the character at the given position
-    fn inner_mut_char(&mut self, pos: TextBufferPos) -> Option<&mut (VGAChar, CharacterColor)> {
+    fn inner_mut_char(&mut self, pos: TextBufferPos) -> Option<&mut Cell> {
         let i = self.index_of(pos)?;
         Some(&mut self.data[i])
     }
 
     /// Get a mutable reference to the character at the given position, try to avoid using this and prefer the instructions which write larger sections of the text buffer at once
-    pub fn mut_char(&mut self, pos: TextBufferPos) -> Option<&mut (VGAChar, CharacterColor)> {
+    pub fn mut_char(&mut self, pos: TextBufferPos) -> Option<&mut Cell> {
         self.add_dirty_rect(TextBufferRect::new(pos.x, pos.y, 1, 1));
         let i = self.index_of(pos)?;
         Some(&mut self.data[i])
     }
 
     /// Get the character at the given position
-    pub fn char_ref(&self, pos: TextBufferPos) -> Option<&(VGAChar, CharacterColor)> {
+    pub fn char_ref(&self, pos: TextBufferPos) -> Option<&Cell> {
         let i = self.index_of(pos)?;
         Some(&self.data[i])
     }
 
-    /// Clear a rectangle
-    pub fn clear_rect(&mut self, rect: TextBufferRect) {
+    /// Fill a rectangle with copies of the given cell
+    pub fn fill_rect(&mut self, rect: TextBufferRect, cell: Cell) {
         for x in rect.x..rect.right() {
             for y in rect.y..rect.bottom() {
                 if let Some(c) = self.inner_mut_char(TextBufferPos{x, y}) {
-                    *c = (VGAChar(b' '), CharacterColor::Gray);
+                    *c = cell;
                 }
             }
         }
@@ -206,11 +231,21 @@ impl TextBufferScreen {
         self.add_dirty_rect(rect);
     }
 
-    /// Write text to the display at a specific location, takes in a slice of VGAChar's and a color for the text
-    pub fn write_text(&mut self, pos: TextBufferPos, text: &[VGAChar], color: CharacterColor) -> TextBufferRect {
+    /// Clear a rectangle
+    pub fn clear_rect(&mut self, rect: TextBufferRect) {
+        self.fill_rect(rect, Cell::blank());
+    }
+
+    /// Clear a rectangle to the given background color
+    pub fn clear_rect_background(&mut self, rect: TextBufferRect, background: Color) {
+        self.fill_rect(rect, Cell::new(VGAChar(b' '), CellStyle::default().background(background)));
+    }
+
+    /// Write text to the display at a specific location, takes in a slice of VGAChar's and a style for the text
+    pub fn write_text(&mut self, pos: TextBufferPos, text: &[VGAChar], style: CellStyle) -> TextBufferRect {
         for (i, c) in text.iter().enumerate() {
             if let Some(cref) = self.inner_mut_char(TextBufferPos{x: pos.x + i as isize, y: pos.y}) {
-                *cref = (*c, color);
+                *cref = Cell::new(*c, style);
             }
         }
 
@@ -219,16 +254,16 @@ impl TextBufferScreen {
         rect
     }
 
-    /// Write text to the display at a specific location respecting text alignment, takes in a slice of VGAChar's and a color for the text
-    pub fn write_text_align(&mut self, pos: TextBufferPos, text: &[VGAChar], color: CharacterColor, align: TextAlign) -> TextBufferRect {
-        self.write_text(align.align_text(pos, text.len()), text, color)
+    /// Write text to the display at a specific location respecting text alignment, takes in a slice of VGAChar's and a style for the text
+    pub fn write_text_align(&mut self, pos: TextBufferPos, text: &[VGAChar], style: CellStyle, align: TextAlign) -> TextBufferRect {
+        self.write_text(align.align_text(pos, text.len()), text, style)
     }
 
     /// Write a string to the display at a specific location. Takes in an &str instead of a slice of VGAChar's. This function attempts to perform the conversion to VGAChar's, returning an error if the conversion cannot be performed
-    pub fn write_string(&mut self, pos: TextBufferPos, text: &str, color: CharacterColor) -> Result<TextBufferRect, char> {
+    pub fn write_string(&mut self, pos: TextBufferPos, text: &str, style: CellStyle) -> Result<TextBufferRect, char> {
         for (i, c) in text.chars().enumerate() {
             if let Some(cref) = self.inner_mut_char(TextBufferPos{x: pos.x + i as isize, y: pos.y}) {
-                *cref = (c.try_into()?, color);
+                *cref = Cell::new(c.try_into()?, style);
             }
         }
 
@@ -239,12 +274,12 @@ impl TextBufferScreen {
     }
 
     /// Write a string to the display at a specific location respecting text alignment. Takes in an &str instead of a slice of VGAChar's. This function attempts to perform the conversion to VGAChar's, returning an error if the conversion cannot be performed
-    pub fn write_string_align(&mut self, pos: TextBufferPos, text: &str, color: CharacterColor, align: TextAlign) -> Result<TextBufferRect, char> {
-        self.write_string(align.align_text(pos, text.len()), text, color)
+    pub fn write_string_align(&mut self, pos: TextBufferPos, text: &str, style: CellStyle, align: TextAlign) -> Result<TextBufferRect, char> {
+        self.write_string(align.align_text(pos, text.len()), text, style)
     }
 
-    /// Write a line of pre colored text to the display at the given position, takes in a slice of (VGAChar, CharacterColor) pairs. This is preferable for staticly allocated text
-    pub fn write_data(&mut self, pos: TextBufferPos, text: &[(VGAChar, CharacterColor)]) -> TextBufferRect {
+    /// Write a line of pre colored text to the display at the given position, takes in a slice of Cells. This is preferable for staticly allocated text
+    pub fn write_data(&mut self, pos: TextBufferPos, text: &[Cell]) -> TextBufferRect {
         for (i, c) in text.iter().enumerate() {
             if let Some(cref) = self.inner_mut_char(TextBufferPos{x: pos.x + i as isize, y: pos.y}) {
                 *cref = *c;
@@ -257,11 +292,27 @@ impl TextBufferScreen {
         rect
     }
 
-    /// Write a line of pre colored text to the display at the given position respecting text alignment, takes in a slice of (VGAChar, CharacterColor) pairs. This is preferable for staticly allocated text
-    pub fn write_data_align(&mut self, pos: TextBufferPos, text: &[(VGAChar, CharacterColor)], align: TextAlign) -> TextBufferRect {
+    /// Write a line of pre colored text to the display at the given position respecting text alignment, takes in a slice of Cells. This is preferable for staticly allocated text
+    pub fn write_data_align(&mut self, pos: TextBufferPos, text: &[Cell], align: TextAlign) -> TextBufferRect {
         self.write_data(align.align_text(pos, text.len()), text)
     }
 
+    /// Returns true if rendering would draw anything, either because cells changed or blinking cells are due to change phase
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty_regions.is_empty() || self.clear_optimization_heuristic || blink_visible() != self.last_blink_visible
+    }
+
+    /// Clear the display and draw every cell on the next render, used when the area the screen is drawn to has moved
+    pub fn invalidate(&mut self) {
+        self.clear_optimization_heuristic = true;
+        self.add_dirty_rect(self.screen_rect());
+    }
+
+    /// Get the regions changed since the screen was last rendered
+    pub fn dirty_regions(&self) -> &[TextBufferRect] {
+        &self.dirty_regions
+    }
+
     /// Get all of the dirty rectangles and clear the already present list
     pub fn take_dirty(&mut self) -> Vec<TextBufferRect> {
         let mut result = vec![];
@@ -277,52 +328,110 @@ impl TextBufferScreen {
         backup
     }
 
-    /// Write the text buffer to a canvas display
-    pub fn write_to_canvas<'a, T: sdl2::render::RenderTarget>(&mut self, canvas: &mut Canvas<T>, character_map: &mut CharacterMap<'a>, pixel_scale: usize) -> Result<(), String> {
-        // Copy the text buffer to the canvas
-        let mut last_color = (255, 255, 255);
-        character_map.texture.set_color_mod(last_color.0, last_color.1, last_color.2);
+    /// Get the palette used to display the named colors
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Replace the palette used to display the named colors, this forces a redraw of the entire screen
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+        self.clear_optimization_heuristic = true;
+        self.add_dirty_rect(self.screen_rect());
+    }
+
+    /// Mark every row span containing blinking cells as dirty, used when the blink clock changes phase
+    pub fn mark_blinking_dirty(&mut self) {
+        for y in 0..self.height {
+            let row = &self.data[y * self.width..(y + 1) * self.width];
+            let first = row.iter().position(|c| c.style.attributes.contains(CellAttributes::BLINK));
+            let last = row.iter().rposition(|c| c.style.attributes.contains(CellAttributes::BLINK));
+
+            if let (Some(first), Some(last)) = (first, last) {
+                self.add_dirty_rect(TextBufferRect::new(first as isize, y as isize, last - first + 1, 1));
+            }
+        }
+    }
+
+    /// Render the dirty regions of the text buffer with the given backend
+    pub fn render(&mut self, backend: &mut (impl RenderBackend + ?Sized)) -> Result<(), String> {
+        let blink_visible = blink_visible();
+        if blink_visible != self.last_blink_visible {
+            self.mark_blinking_dirty();
+            self.last_blink_visible = blink_visible;
+        }
 
         let mut dirty_rects = self.take_dirty();
         let clear_optimization = self.get_and_clear_optimization_flag();
 
         if clear_optimization {
-            canvas.clear();
             dirty_rects.clear();
             dirty_rects.push(self.screen_rect());
         }
 
+        let context = RenderContext {
+            palette: &self.palette,
+            blink_visible
+        };
+
+        backend.begin_frame((self.width, self.height), clear_optimization, &context)?;
+
         for dirty_rect in dirty_rects {
             for x in dirty_rect.x..dirty_rect.right() {
                 for y in dirty_rect.y..dirty_rect.bottom() {
-                    if let Some((character, color)) = self.char_ref(TextBufferPos{x, y}) {
-                        if !(clear_optimization && *character == VGAChar(b' ')) {
-                            let source_rect = character_map.get_rect(character.0.into());
-                            let dest_rect = character_map.get_dest_rect(x, y, pixel_scale);
-
-                            let (r, g, b) = color.into();
-                            if (r, g, b) != last_color {
-                                character_map.texture.set_color_mod(r, g, b);
-                                last_color = (r, g, b);
-                            }
-
-                            canvas.copy(&character_map.texture,
-                                Some(source_rect),
-                                Some(dest_rect)).map_err(|e| e.to_string())?;
+                    if let Some(cell) = self.char_ref(TextBufferPos{x, y}) {
+                        if !(clear_optimization && cell.is_blank()) {
+                            backend.draw_cell(TextBufferPos{x, y}, cell, &context)?;
                         }
                     }
                 }
             }
         }
 
-        canvas.present();
+        backend.end_frame()
+    }
 
-        Ok(())
+    /// Write the text buffer to a canvas display
+    #[cfg(feature = "sdl")]
+    pub fn write_to_canvas<T: sdl2::render::RenderTarget>(&mut self, canvas: &mut sdl2::render::Canvas<T>, character_map: &mut crate::character_map::CharacterMap, pixel_scale: usize) -> Result<(), String> {
+        self.render(&mut crate::backend::SdlBackend::new(canvas, character_map, pixel_scale))
+    }
+
+    /// Write the text buffer to a viewport of a canvas display
+    #[cfg(feature = "sdl")]
+    pub fn write_to_viewport<T: sdl2::render::RenderTarget>(&mut self, canvas: &mut sdl2::render::Canvas<T>, character_map: &mut crate::character_map::CharacterMap, viewport: &crate::scaling::Viewport) -> Result<(), String> {
+        self.render(&mut crate::backend::SdlBackend::with_viewport(canvas, character_map, viewport))
+    }
+
+    /// Render every cell of the text buffer to a backend as a cleared frame, without touching the dirty regions used by `render`
+    pub fn render_full(&self, backend: &mut (impl RenderBackend + ?Sized)) -> Result<(), String> {
+        let context = RenderContext {
+            palette: &self.palette,
+            blink_visible: blink_visible()
+        };
+
+        backend.begin_frame((self.width, self.height), true, &context)?;
+
+        for (i, cell) in self.data.iter().enumerate() {
+            if !cell.is_blank() {
+                backend.draw_cell(TextBufferPos{x: (i % self.width) as isize, y: (i / self.width) as isize}, cell, &context)?;
+            }
+        }
+
+        backend.end_frame()
+    }
+
+    /// Rasterize the text buffer into an image using a software font, pixel scale is a percentage of the font's character size
+    pub fn screenshot(&self, font: &crate::raster::SoftwareFont, pixel_scale: usize) -> Result<crate::raster::RgbaImage, String> {
+        let mut backend = crate::backend::ImageBackend::new(font, pixel_scale);
+        self.render_full(&mut backend)?;
+
+        Ok(backend.into_image())
     }
 
     /// Resize the text buffer
     pub fn resize_buffer(&mut self, width: usize, height: usize) {
-        let mut new_data = vec![(VGAChar(b' '), CharacterColor::Gray); width * height];
+        let mut new_data = vec![Cell::blank(); width * height];
 
         for x in 0..self.width.min(width) {
             for y in 0..self.height.min(height) {
@@ -359,17 +468,17 @@ impl TextBufferInterface for TextBufferScreen {
         self.index_of(pos)
     }
 
-    fn inner_data_buffer(&self) -> &[(VGAChar, CharacterColor)] {
+    fn inner_data_buffer(&self) -> &[Cell] {
         &self.data
     }
 
-    fn inner_data_buffer_mut(&mut self) -> &mut [(VGAChar, CharacterColor)] {
+    fn inner_data_buffer_mut(&mut self) -> &mut [Cell] {
         &mut self.data
     }
 
-    fn clear_rect(&mut self, rect: TextBufferRect) {
+    fn fill_rect(&mut self, rect: TextBufferRect, cell: Cell) {
         if let Some(rect) = rect.intersection(&self.screen_rect()) {
-            self.clear_rect(rect);
+            self.fill_rect(rect, cell);
         }
     }
 
@@ -416,22 +525,22 @@ impl<'a> TextBufferInterface for TextView<'a> {
         }
     }
 
-    fn inner_data_buffer(&self) -> &[(VGAChar, CharacterColor)] {
+    fn inner_data_buffer(&self) -> &[Cell] {
         self.parent_buffer.inner_data_buffer()
     }
 
-    fn inner_data_buffer_mut(&mut self) -> &mut [(VGAChar, CharacterColor)] {
+    fn inner_data_buffer_mut(&mut self) -> &mut [Cell] {
         self.parent_buffer.inner_data_buffer_mut()
     }
 
-    fn clear_rect(&mut self, rect: TextBufferRect) {
+    fn fill_rect(&mut self, rect: TextBufferRect, cell: Cell) {
         if let Some(rect) = self.screen_rect().intersection(&rect) {
-            self.parent_buffer.clear_rect(TextBufferRect {
+            self.parent_buffer.fill_rect(TextBufferRect {
                 x: self.rect.x + rect.x.max(0),
                 y: self.rect.y + rect.y.max(0),
                 width: rect.width.min(self.width()),
-                height: rect.height.min(self.width()),
-            })
+                height: rect.height.min(self.height()),
+            }, cell)
         }
     }
 
@@ -0,0 +1,212 @@
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use super::{VGAChar, CharacterColor, Color, Palette};
+
+/// Number of milliseconds blinking cells spend in each phase of the blink clock
+static BLINK_INTERVAL: AtomicU64 = AtomicU64::new(500);
+
+/// Time at which the blink clock was first queried
+static BLINK_EPOCH: OnceLock<Instant> = OnceLock::new();
+
+/// Set the number of milliseconds blinking cells spend visible or hidden
+pub fn set_blink_interval(milliseconds: u64) {
+    BLINK_INTERVAL.store(milliseconds.max(1), Ordering::Relaxed);
+}
+
+/// Returns true if blinking cells should currently be drawn, based on the global blink clock
+pub fn blink_visible() -> bool {
+    let elapsed = BLINK_EPOCH.get_or_init(Instant::now).elapsed().as_millis() as u64;
+
+    (elapsed / BLINK_INTERVAL.load(Ordering::Relaxed)) % 2 == 0
+}
+
+/// Set of display attribute flags for a cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CellAttributes(pub u8);
+
+impl CellAttributes {
+    pub const NONE: CellAttributes = CellAttributes(0);
+    pub const REVERSE: CellAttributes = CellAttributes(1 << 0);
+    pub const UNDERLINE: CellAttributes = CellAttributes(1 << 1);
+    pub const BLINK: CellAttributes = CellAttributes(1 << 2);
+    pub const DIM: CellAttributes = CellAttributes(1 << 3);
+    pub const STRIKETHROUGH: CellAttributes = CellAttributes(1 << 4);
+
+    /// Returns true if no attributes are set
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if every attribute in other is also set in self
+    pub const fn contains(&self, other: CellAttributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Get the combination of the attributes in self and other
+    pub const fn union(self, other: CellAttributes) -> Self {
+        CellAttributes(self.0 | other.0)
+    }
+
+    /// Get the attributes in self which are not set in other
+    pub const fn difference(self, other: CellAttributes) -> Self {
+        CellAttributes(self.0 & !other.0)
+    }
+}
+
+impl std::ops::BitOr for CellAttributes {
+    type Output = CellAttributes;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl std::ops::BitOrAssign for CellAttributes {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs);
+    }
+}
+
+/// Foreground and background colors of a cell along with its display attributes, similar to the attribute byte in VGA text mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellStyle {
+    pub foreground: Color,
+    pub background: Color,
+    pub attributes: CellAttributes
+}
+
+impl CellStyle {
+    /// Construct a new cell style from a named foreground and background color
+    pub const fn new(foreground: CharacterColor, background: CharacterColor) -> Self {
+        Self::from_colors(Color::Named(foreground), Color::Named(background))
+    }
+
+    /// Construct a new cell style from arbitrary foreground and background colors
+    pub const fn from_colors(foreground: Color, background: Color) -> Self {
+        Self {
+            foreground,
+            background,
+            attributes: CellAttributes::NONE
+        }
+    }
+
+    /// Replace the foreground color of the style
+    pub const fn foreground(mut self, color: Color) -> Self {
+        self.foreground = color;
+        self
+    }
+
+    /// Replace the background color of the style
+    pub const fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// Replace the attributes of the style
+    pub const fn attributes(mut self, attributes: CellAttributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Add attributes to the style
+    pub const fn with(mut self, attributes: CellAttributes) -> Self {
+        self.attributes = self.attributes.union(attributes);
+        self
+    }
+
+    /// Get the foreground and background colors to display after applying the reverse and dim attributes
+    pub fn display_colors(&self, palette: &Palette) -> ((u8, u8, u8), (u8, u8, u8)) {
+        let (foreground, background) = if self.attributes.contains(CellAttributes::REVERSE) {
+            (&self.background, &self.foreground)
+        }
+        else {
+            (&self.foreground, &self.background)
+        };
+
+        let foreground = foreground.resolve(palette);
+        let foreground = if self.attributes.contains(CellAttributes::DIM) {
+            (foreground.0 / 2, foreground.1 / 2, foreground.2 / 2)
+        }
+        else {
+            foreground
+        };
+
+        (foreground, background.resolve(palette))
+    }
+
+    /// Returns true if the glyph should be drawn given the current phase of the blink clock
+    pub const fn glyph_visible(&self, blink_visible: bool) -> bool {
+        blink_visible || !self.attributes.contains(CellAttributes::BLINK)
+    }
+}
+
+impl std::default::Default for CellStyle {
+    fn default() -> Self {
+        Self::new(CharacterColor::Gray, CharacterColor::Black)
+    }
+}
+
+impl std::convert::From<CharacterColor> for CellStyle {
+    fn from(color: CharacterColor) -> Self {
+        Self::new(color, CharacterColor::Black)
+    }
+}
+
+impl std::convert::From<Color> for CellStyle {
+    fn from(color: Color) -> Self {
+        Self::from_colors(color, Color::Named(CharacterColor::Black))
+    }
+}
+
+impl std::convert::From<(CharacterColor, CharacterColor)> for CellStyle {
+    fn from(data: (CharacterColor, CharacterColor)) -> Self {
+        Self::new(data.0, data.1)
+    }
+}
+
+/// A single character cell in a text buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub character: VGAChar,
+    pub style: CellStyle
+}
+
+impl Cell {
+    /// Construct a new cell from a character and a style
+    pub const fn new(character: VGAChar, style: CellStyle) -> Self {
+        Self {
+            character,
+            style
+        }
+    }
+
+    /// Construct the cell used when clearing a region of a text buffer
+    pub const fn blank() -> Self {
+        Self::new(VGAChar(b' '), CellStyle::new(CharacterColor::Gray, CharacterColor::Black))
+    }
+
+    /// Returns true if drawing the cell would not change a cleared screen
+    pub fn is_blank(&self) -> bool {
+        self.character == VGAChar(b' ') && self.style.background == Color::Named(CharacterColor::Black) && self.style.attributes.is_empty()
+    }
+}
+
+impl std::default::Default for Cell {
+    fn default() -> Self {
+        Self::blank()
+    }
+}
+
+impl std::convert::From<(VGAChar, CharacterColor)> for Cell {
+    fn from(data: (VGAChar, CharacterColor)) -> Self {
+        Self::new(data.0, data.1.into())
+    }
+}
+
+impl std::convert::From<(VGAChar, CellStyle)> for Cell {
+    fn from(data: (VGAChar, CellStyle)) -> Self {
+        Self::new(data.0, data.1)
+    }
+}
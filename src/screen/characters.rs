@@ -1,5 +1,6 @@
+use super::Palette;
 
-pub const ASCII_MAP: &'static [char; 256] = 
+pub const ASCII_MAP: &[char; 256] = 
 &['\0', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼', ' ', '!', '\"', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?', '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^', '_', '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '~', '⌂', 'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', 'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', ' '];
 
 /// Representation of a codepage 437 character with methods for converting unicode symbols into and out of the codepage
@@ -275,9 +276,9 @@ impl VGAChar {
     }
 }
 
-impl std::convert::Into<char> for VGAChar {
-    fn into(self) -> char {
-        ASCII_MAP[self.0 as usize]
+impl std::convert::From<VGAChar> for char {
+    fn from(val: VGAChar) -> Self {
+        ASCII_MAP[val.0 as usize]
     }
 }
 
@@ -311,25 +312,78 @@ pub enum CharacterColor {
     BrightWhite
 }
 
+impl CharacterColor {
+    /// All of the named colors, in palette order
+    pub const ALL: [CharacterColor; 16] = [
+        CharacterColor::Black,
+        CharacterColor::Blue,
+        CharacterColor::Green,
+        CharacterColor::Cyan,
+        CharacterColor::Red,
+        CharacterColor::Magenta,
+        CharacterColor::Brown,
+        CharacterColor::White,
+        CharacterColor::Gray,
+        CharacterColor::LightBlue,
+        CharacterColor::LightGreen,
+        CharacterColor::LightCyan,
+        CharacterColor::LightRed,
+        CharacterColor::LightMagenta,
+        CharacterColor::Yellow,
+        CharacterColor::BrightWhite
+    ];
+
+    /// Get the index of the color in a palette
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Get the color at the given palette index, only the lower four bits are used
+    pub const fn from_index(index: usize) -> Self {
+        Self::ALL[index & 0xF]
+    }
+
+    /// Get the ANSI color number of the color without its brightness bit, ANSI orders red and blue opposite to VGA
+    pub const fn ansi_index(self) -> usize {
+        let index = self.index() & 0x7;
+        (index & 0b010) | ((index & 0b001) << 2) | ((index & 0b100) >> 2)
+    }
+
+    /// Get the color for an ANSI color number from 0 to 7, using the bright variant if requested
+    pub const fn from_ansi_index(index: usize, bright: bool) -> Self {
+        let index = index & 0x7;
+        let vga = (index & 0b010) | ((index & 0b001) << 2) | ((index & 0b100) >> 2);
+        Self::from_index(if bright { vga | 0x8 } else { vga })
+    }
+
+    /// Get a color from its name, ignoring case, spaces and underscores, so "light_red" and "Light Red" are both accepted
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name: String = name.chars().filter(|c| !matches!(c, ' ' | '_' | '-')).flat_map(|c| c.to_lowercase()).collect();
+
+        Some(match name.as_str() {
+            "black" => CharacterColor::Black,
+            "blue" => CharacterColor::Blue,
+            "green" => CharacterColor::Green,
+            "cyan" => CharacterColor::Cyan,
+            "red" => CharacterColor::Red,
+            "magenta" => CharacterColor::Magenta,
+            "brown" => CharacterColor::Brown,
+            "white" => CharacterColor::White,
+            "gray" | "grey" => CharacterColor::Gray,
+            "lightblue" => CharacterColor::LightBlue,
+            "lightgreen" => CharacterColor::LightGreen,
+            "lightcyan" => CharacterColor::LightCyan,
+            "lightred" => CharacterColor::LightRed,
+            "lightmagenta" => CharacterColor::LightMagenta,
+            "yellow" => CharacterColor::Yellow,
+            "brightwhite" => CharacterColor::BrightWhite,
+            _ => return None
+        })
+    }
+}
+
 impl std::convert::From<&CharacterColor> for (u8, u8, u8) {
     fn from(c: &CharacterColor) -> Self {
-        match c {
-            CharacterColor::Black => (0, 0, 0),
-            CharacterColor::Blue => (0, 0, 176),
-            CharacterColor::Green => (0, 176, 0),
-            CharacterColor::Cyan => (0, 176, 176),
-            CharacterColor::Red => (176, 0, 0),
-            CharacterColor::Magenta => (176, 0, 176),
-            CharacterColor::Brown => (176, 96, 0),
-            CharacterColor::White => (176, 176, 176),
-            CharacterColor::Gray => (96, 96, 96),
-            CharacterColor::LightBlue => (96, 96, 255),
-            CharacterColor::LightGreen => (96, 255, 96),
-            CharacterColor::LightCyan => (96, 255, 255),
-            CharacterColor::LightRed => (255, 96, 96),
-            CharacterColor::LightMagenta => (255, 0, 255),
-            CharacterColor::Yellow => (255, 255, 96),
-            CharacterColor::BrightWhite => (255, 255, 255),
-        }
+        Palette::VGA.get(*c)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,162 @@
+use super::CharacterColor;
+
+/// A color which is either one of the 16 named palette colors or an exact 24-bit RGB value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Named(CharacterColor),
+    Rgb(u8, u8, u8)
+}
+
+impl Color {
+    /// Resolve the color to an RGB triple using the given palette for named colors
+    pub const fn resolve(&self, palette: &Palette) -> (u8, u8, u8) {
+        match self {
+            Color::Named(c) => palette.get(*c),
+            Color::Rgb(r, g, b) => (*r, *g, *b)
+        }
+    }
+}
+
+impl std::convert::From<CharacterColor> for Color {
+    fn from(c: CharacterColor) -> Self {
+        Color::Named(c)
+    }
+}
+
+impl std::convert::From<(u8, u8, u8)> for Color {
+    fn from(data: (u8, u8, u8)) -> Self {
+        Color::Rgb(data.0, data.1, data.2)
+    }
+}
+
+/// Table used by renderers to convert the 16 named colors to RGB values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: [(u8, u8, u8); 16]
+}
+
+impl Palette {
+    /// The default palette of the engine
+    pub const VGA: Palette = Palette::new([
+        (0, 0, 0),
+        (0, 0, 176),
+        (0, 176, 0),
+        (0, 176, 176),
+        (176, 0, 0),
+        (176, 0, 176),
+        (176, 96, 0),
+        (176, 176, 176),
+        (96, 96, 96),
+        (96, 96, 255),
+        (96, 255, 96),
+        (96, 255, 255),
+        (255, 96, 96),
+        (255, 0, 255),
+        (255, 255, 96),
+        (255, 255, 255)
+    ]);
+
+    /// The classic CGA palette, including the darkened yellow for brown
+    pub const CGA: Palette = Palette::new([
+        (0x00, 0x00, 0x00),
+        (0x00, 0x00, 0xAA),
+        (0x00, 0xAA, 0x00),
+        (0x00, 0xAA, 0xAA),
+        (0xAA, 0x00, 0x00),
+        (0xAA, 0x00, 0xAA),
+        (0xAA, 0x55, 0x00),
+        (0xAA, 0xAA, 0xAA),
+        (0x55, 0x55, 0x55),
+        (0x55, 0x55, 0xFF),
+        (0x55, 0xFF, 0x55),
+        (0x55, 0xFF, 0xFF),
+        (0xFF, 0x55, 0x55),
+        (0xFF, 0x55, 0xFF),
+        (0xFF, 0xFF, 0x55),
+        (0xFF, 0xFF, 0xFF)
+    ]);
+
+    /// A low contrast palette inspired by Solarized, black maps to the dark background tone
+    pub const SOLARIZED: Palette = Palette::new([
+        (0x00, 0x2B, 0x36),
+        (0x26, 0x8B, 0xD2),
+        (0x85, 0x99, 0x00),
+        (0x2A, 0xA1, 0x98),
+        (0xDC, 0x32, 0x2F),
+        (0xD3, 0x36, 0x82),
+        (0xB5, 0x89, 0x00),
+        (0x93, 0xA1, 0xA1),
+        (0x58, 0x6E, 0x75),
+        (0x6C, 0x71, 0xC4),
+        (0xB5, 0xC2, 0x36),
+        (0x58, 0xC6, 0xBC),
+        (0xCB, 0x4B, 0x16),
+        (0xEC, 0x68, 0xA4),
+        (0xE6, 0xBE, 0x3C),
+        (0xFD, 0xF6, 0xE3)
+    ]);
+
+    /// The luminance of each color in the VGA palette
+    pub const GRAYSCALE: Palette = Palette::new([
+        (0, 0, 0),
+        (20, 20, 20),
+        (103, 103, 103),
+        (123, 123, 123),
+        (53, 53, 53),
+        (73, 73, 73),
+        (109, 109, 109),
+        (176, 176, 176),
+        (96, 96, 96),
+        (114, 114, 114),
+        (189, 189, 189),
+        (207, 207, 207),
+        (144, 144, 144),
+        (105, 105, 105),
+        (237, 237, 237),
+        (255, 255, 255)
+    ]);
+
+    /// Construct a new palette from the RGB values of the 16 named colors, in `CharacterColor` order
+    pub const fn new(colors: [(u8, u8, u8); 16]) -> Self {
+        Self {
+            colors
+        }
+    }
+
+    /// Get the RGB value of a named color
+    pub const fn get(&self, color: CharacterColor) -> (u8, u8, u8) {
+        self.colors[color.index()]
+    }
+
+    /// Replace the RGB value of a named color
+    pub fn set(&mut self, color: CharacterColor, rgb: (u8, u8, u8)) {
+        self.colors[color.index()] = rgb;
+    }
+
+    /// Resolve any color to an RGB triple
+    pub const fn resolve(&self, color: &Color) -> (u8, u8, u8) {
+        color.resolve(self)
+    }
+
+    /// Get the RGB values of all of the named colors
+    pub const fn colors(&self) -> &[(u8, u8, u8); 16] {
+        &self.colors
+    }
+
+    /// Find the named color closest to an RGB triple
+    pub fn nearest(&self, rgb: (u8, u8, u8)) -> CharacterColor {
+        let distance = |c: &(u8, u8, u8)| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(c.0, rgb.0) + d(c.1, rgb.1) + d(c.2, rgb.2)
+        };
+
+        let index = (0..16).min_by_key(|i| distance(&self.colors[*i])).unwrap();
+        CharacterColor::from_index(index)
+    }
+}
+
+impl std::default::Default for Palette {
+    fn default() -> Self {
+        Self::VGA
+    }
+}
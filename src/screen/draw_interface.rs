@@ -1,18 +1,16 @@
-use std::{fmt::Display, borrow::Borrow};
-
-use super::{TextBufferPos, TextBufferRect, TextBufferInterface, CharacterColor, TextAlign};
+use super::{TextBufferPos, TextBufferRect, TextBufferInterface, CharacterColor, CellStyle, CellAttributes, TextAlign};
 
 /// A trait which grants objects the ability to be drawn to a text buffer surface
 pub trait Drawable<Settings> {
     /// Draws this object to the text buffer interface at the given position
-    fn draw(&self, screen: &mut impl TextBufferInterface, pos: TextBufferPos, settings: &Settings) -> TextBufferRect;
+    fn draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, settings: &Settings) -> TextBufferRect;
 }
 
 /// Text drawable trait to allow the simplest implementation of Drawable
 pub trait TextDrawable<Settings> {
     /// Draws this object to the text buffer interface at the given position
-    fn text_draw(&self, screen: &mut impl TextBufferInterface, pos: TextBufferPos, _settings: &Settings) -> TextBufferRect {
-        screen.write_string(pos, self.as_str(), CharacterColor::BrightWhite).unwrap()
+    fn text_draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, _settings: &Settings) -> TextBufferRect {
+        screen.write_string(pos, self.as_str(), CharacterColor::BrightWhite.into()).unwrap()
     }
 
     /// Get an &str representation of the object, implementing this function enables simple use of this trait to display text like objects
@@ -22,36 +20,50 @@ pub trait TextDrawable<Settings> {
 }
 
 impl<T, Settings> Drawable<Settings> for T where T: TextDrawable<Settings> {
-    fn draw(&self, screen: &mut impl TextBufferInterface, pos: TextBufferPos, settings: &Settings) -> TextBufferRect {
+    fn draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, settings: &Settings) -> TextBufferRect {
         self.text_draw(screen, pos, settings)
     }
 }
 
 pub struct TextFormatting {
-    pub color: CharacterColor,
+    pub style: CellStyle,
     pub alignment: TextAlign
 }
 
+impl TextFormatting {
+    /// Add display attributes to the formatting
+    pub const fn with(mut self, attributes: CellAttributes) -> Self {
+        self.style = self.style.with(attributes);
+        self
+    }
+}
+
 impl std::convert::From<(CharacterColor, TextAlign)> for TextFormatting {
     fn from(data: (CharacterColor, TextAlign)) -> Self {
-        TextFormatting { color: data.0, alignment: data.1 }
+        TextFormatting { style: data.0.into(), alignment: data.1 }
+    }
+}
+
+impl std::convert::From<(CellStyle, TextAlign)> for TextFormatting {
+    fn from(data: (CellStyle, TextAlign)) -> Self {
+        TextFormatting { style: data.0, alignment: data.1 }
     }
 }
 
 impl TextDrawable<TextFormatting> for String {
-    fn text_draw(&self, screen: &mut impl TextBufferInterface, pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
-        screen.write_string_align(pos, &self.to_string(), settings.color, settings.alignment).unwrap()
+    fn text_draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
+        screen.write_string_align(pos, &self.to_string(), settings.style, settings.alignment).unwrap()
     }
 }
 
-impl<'a> TextDrawable<TextFormatting> for &'a str {
-    fn text_draw(&self, screen: &mut impl TextBufferInterface, pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
-        screen.write_string_align(pos, self, settings.color, settings.alignment).unwrap()
+impl TextDrawable<TextFormatting> for &str {
+    fn text_draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
+        screen.write_string_align(pos, self, settings.style, settings.alignment).unwrap()
     }
 }
 
 impl<U, V: Sized> TextDrawable<U> for std::sync::Arc<std::cell::RefCell<V>> where V: TextDrawable<U> {
-    fn text_draw(&self, screen: &mut impl TextBufferInterface, pos: TextBufferPos, settings: &U) -> TextBufferRect {
+    fn text_draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, settings: &U) -> TextBufferRect {
         self.try_borrow().unwrap().text_draw(screen, pos, settings)
     }
 } 
\ No newline at end of file
@@ -0,0 +1,190 @@
+use super::{Cell, CellAttributes, CellStyle, CharacterColor, Color, Drawable, TextBufferInterface, TextBufferPos, TextBufferRect, TextFormatting, VGAChar};
+
+/// Changes a markup tag makes to the style text is drawn with, anything left as None keeps the base style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MarkupStyle {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub attributes: CellAttributes
+}
+
+impl MarkupStyle {
+    /// Apply the changes to a base style
+    pub fn apply(&self, base: CellStyle) -> CellStyle {
+        CellStyle {
+            foreground: self.foreground.unwrap_or(base.foreground),
+            background: self.background.unwrap_or(base.background),
+            attributes: base.attributes.union(self.attributes)
+        }
+    }
+
+    /// Get the style of a tag nested inside this one
+    fn nest(&self, inner: MarkupStyle) -> MarkupStyle {
+        MarkupStyle {
+            foreground: inner.foreground.or(self.foreground),
+            background: inner.background.or(self.background),
+            attributes: self.attributes.union(inner.attributes)
+        }
+    }
+
+    /// Parse the contents of a tag such as "red", "yellow on blue", "#ff8800 underline" or "on gray"
+    fn parse_tag(tag: &str) -> Result<MarkupStyle, String> {
+        let mut style = MarkupStyle::default();
+        let mut words = tag.split_whitespace();
+
+        while let Some(word) = words.next() {
+            if word == "on" {
+                let color = words.next().ok_or_else(|| format!("Missing background color in markup tag \"{}\"", tag))?;
+                style.background = Some(parse_color(color).ok_or_else(|| format!("Unknown color \"{}\" in markup tag \"{}\"", color, tag))?);
+                continue;
+            }
+
+            let attribute = match word {
+                "u" | "underline" => Some(CellAttributes::UNDERLINE),
+                "blink" => Some(CellAttributes::BLINK),
+                "reverse" => Some(CellAttributes::REVERSE),
+                "dim" => Some(CellAttributes::DIM),
+                "s" | "strike" | "strikethrough" => Some(CellAttributes::STRIKETHROUGH),
+                _ => None
+            };
+
+            match (attribute, parse_color(word)) {
+                (Some(attribute), _) => style.attributes |= attribute,
+                (None, Some(color)) => style.foreground = Some(color),
+                (None, None) => return Err(format!("Unknown markup tag \"{}\"", tag))
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+/// Parse a named color or a #rrggbb color
+fn parse_color(text: &str) -> Option<Color> {
+    match text.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 => {
+            let value = u32::from_str_radix(hex, 16).ok()?;
+            Some(Color::Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8))
+        }
+        Some(_) => None,
+        None => CharacterColor::from_name(text).map(Color::Named)
+    }
+}
+
+/// A run of text drawn in one style
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkupSpan {
+    pub text: Vec<VGAChar>,
+    pub style: MarkupStyle
+}
+
+/// Text with inline style tags, such as "You hit the [red]orc[/] for [yellow on blue]5[/]".
+/// Tags set named or #rrggbb colors, "on" sets the background, and underline, blink, reverse, dim and strike add attributes.
+/// Tags nest and [/] closes the innermost one, tags left open run to the end of the text. A literal [ is written as [[
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Markup {
+    lines: Vec<Vec<MarkupSpan>>
+}
+
+impl Markup {
+    /// Parse markup, failing on unknown or unterminated tags, unmatched closing tags and characters outside of code page 437
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = vec![Vec::new()];
+        let mut stack = vec![MarkupStyle::default()];
+        let mut current = Vec::new();
+        let mut chars = text.chars().peekable();
+
+        fn finish(lines: &mut [Vec<MarkupSpan>], current: &mut Vec<VGAChar>, style: MarkupStyle) {
+            if !current.is_empty() {
+                lines.last_mut().unwrap().push(MarkupSpan { text: std::mem::take(current), style });
+            }
+        }
+
+        while let Some(c) = chars.next() {
+            let style = *stack.last().unwrap();
+
+            match c {
+                '[' if chars.peek() == Some(&'[') => {
+                    chars.next();
+                    current.push(VGAChar(b'['));
+                }
+                '[' => {
+                    let mut tag = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => tag.push(c),
+                            None => return Err(format!("Unterminated markup tag \"[{}\"", tag))
+                        }
+                    }
+
+                    finish(&mut lines, &mut current, style);
+
+                    if tag.trim() == "/" {
+                        if stack.len() == 1 {
+                            return Err("Closing markup tag without an open tag".to_string());
+                        }
+                        stack.pop();
+                    }
+                    else {
+                        stack.push(style.nest(MarkupStyle::parse_tag(&tag)?));
+                    }
+                }
+                '\n' => {
+                    finish(&mut lines, &mut current, style);
+                    lines.push(Vec::new());
+                }
+                c => current.push(VGAChar::from_char(c).ok_or_else(|| format!("Character '{}' can not be shown", c))?)
+            }
+        }
+
+        finish(&mut lines, &mut current, *stack.last().unwrap());
+        Ok(Self { lines })
+    }
+
+    /// Escape text so it is shown as is when placed inside markup
+    pub fn escape(text: &str) -> String {
+        text.replace('[', "[[")
+    }
+
+    /// Get the styled runs of each line
+    pub fn lines(&self) -> &[Vec<MarkupSpan>] {
+        &self.lines
+    }
+
+    /// Get the cells of one line drawn over a base style
+    pub fn line_cells(&self, line: usize, base: CellStyle) -> Vec<Cell> {
+        self.lines.get(line).map(|spans| {
+            spans.iter()
+                .flat_map(|span| span.text.iter().map(move |c| Cell::new(*c, span.style.apply(base))))
+                .collect()
+        }).unwrap_or_default()
+    }
+
+    /// Get the cells of all of the text drawn over a base style, with the lines joined together. This is the form taken by write_data
+    pub fn cells(&self, base: CellStyle) -> Vec<Cell> {
+        (0..self.lines.len()).flat_map(|line| self.line_cells(line, base)).collect()
+    }
+}
+
+impl std::str::FromStr for Markup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Markup::parse(s)
+    }
+}
+
+impl Drawable<TextFormatting> for Markup {
+    fn draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
+        let mut total: Option<TextBufferRect> = None;
+
+        for line in 0..self.lines.len() {
+            let cells = self.line_cells(line, settings.style);
+            let rect = screen.write_data_align(TextBufferPos { x: pos.x, y: pos.y + line as isize }, &cells, settings.alignment);
+            total = Some(total.map_or(rect, |t| t.union(&rect)));
+        }
+
+        total.unwrap_or(TextBufferRect::new(pos.x, pos.y, 0, 0))
+    }
+}
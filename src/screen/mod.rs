@@ -1,11 +1,20 @@
 pub mod buffer;
 pub use buffer::*;
 
+pub mod cell;
+pub use cell::*;
+
 pub mod characters;
 pub use characters::*;
 
+pub mod color;
+pub use color::*;
+
 pub mod draw_interface;
 pub use draw_interface::*;
 
+pub mod markup;
+pub use markup::*;
+
 pub mod structs;
 pub use structs::*;
\ No newline at end of file
@@ -32,13 +32,13 @@ impl TextBufferRect {
         }
     }
 
-    /// Get the interior of the rectangle
+    /// Get the interior of the rectangle, which is empty for rects smaller than 2 by 2
     pub const fn interior(&self) -> TextBufferRect {
         Self {
             x: self.x + 1,
             y: self.y + 1,
-            width: self.width - 2,
-            height: self.height - 2
+            width: self.width.saturating_sub(2),
+            height: self.height.saturating_sub(2)
         }
     }
 
@@ -64,10 +64,8 @@ impl TextBufferRect {
 
     /// Check if two rects overlap
     pub const fn overlap(&self, other: &TextBufferRect) -> bool {
-        self.contains_point(other.x, other.y) ||
-        self.contains_point(other.x, other.bottom() - 1) ||
-        self.contains_point(other.right() - 1, other.y) ||
-        self.contains_point(other.right() - 1, other.bottom() - 1)
+        self.x < other.right() && other.x < self.right() &&
+        self.y < other.bottom() && other.y < self.bottom()
     }
 
     /// Get the intersection of two rects
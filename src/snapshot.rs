@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use crate::screen::{Cell, CharacterColor, Color, TextBufferInterface, TextBufferPos};
+
+/// Environment variable which, when set to 1, makes snapshot assertions write their golden files instead of comparing against them
+pub const UPDATE_SNAPSHOTS_VAR: &str = "UPDATE_SNAPSHOTS";
+
+/// Build one line of text per row of a grid, trailing spaces are trimmed so golden files survive editors
+fn layer(size: (usize, usize), f: impl Fn(usize, usize) -> char) -> String {
+    let mut result = String::new();
+
+    for y in 0..size.1 {
+        let line: String = (0..size.0).map(|x| f(x, y)).collect();
+        result += line.trim_end_matches(' ');
+        result.push('\n');
+    }
+
+    result
+}
+
+/// Get the character used for a color in a color layer, named colors are their hex palette index and exact colors are '#'
+fn color_char(color: &Color) -> char {
+    match color {
+        Color::Named(c) => char::from_digit(c.index() as u32, 16).unwrap(),
+        Color::Rgb(..) => '#'
+    }
+}
+
+/// Dump a grid of cells as plain text using the codepage mapping
+pub fn text_layer(size: (usize, usize), cell: impl Fn(usize, usize) -> Cell) -> String {
+    layer(size, |x, y| match cell(x, y).character.to_char() {
+        '\0' => ' ',
+        c => c
+    })
+}
+
+/// Dump the foreground colors of a grid of cells, one character per cell
+pub fn foreground_layer(size: (usize, usize), cell: impl Fn(usize, usize) -> Cell) -> String {
+    layer(size, |x, y| color_char(&cell(x, y).style.foreground))
+}
+
+/// Dump the background colors of a grid of cells, one character per cell. Black backgrounds are shown as spaces so the layer stays readable
+pub fn background_layer(size: (usize, usize), cell: impl Fn(usize, usize) -> Cell) -> String {
+    layer(size, |x, y| match cell(x, y).style.background {
+        Color::Named(CharacterColor::Black) => ' ',
+        c => color_char(&c)
+    })
+}
+
+/// Dump the attribute flags of a grid of cells as one base 32 digit per cell, cells without attributes are shown as spaces
+pub fn attribute_layer(size: (usize, usize), cell: impl Fn(usize, usize) -> Cell) -> String {
+    layer(size, |x, y| match cell(x, y).style.attributes {
+        a if a.is_empty() => ' ',
+        a => char::from_digit(a.0 as u32 & 0x1F, 32).unwrap()
+    })
+}
+
+/// Dump a grid of cells as text followed by its foreground, background and attribute layers
+pub fn text_and_color_layers(size: (usize, usize), cell: impl Fn(usize, usize) -> Cell) -> String {
+    format!("{}--- foreground\n{}--- background\n{}--- attributes\n{}",
+        text_layer(size, &cell), foreground_layer(size, &cell), background_layer(size, &cell), attribute_layer(size, &cell))
+}
+
+/// Read the cell of a text buffer interface, treating positions outside of the buffer as blank
+fn buffer_cell(screen: &(impl TextBufferInterface + ?Sized), x: usize, y: usize) -> Cell {
+    screen.char_ref(TextBufferPos { x: x as isize, y: y as isize }).copied().unwrap_or_default()
+}
+
+/// Dump the contents of a text buffer, view or widget buffer as plain text
+pub fn text_snapshot(screen: &(impl TextBufferInterface + ?Sized)) -> String {
+    text_layer((screen.width(), screen.height()), |x, y| buffer_cell(screen, x, y))
+}
+
+/// Dump the contents of a text buffer, view or widget buffer as text along with its color and attribute layers
+pub fn color_snapshot(screen: &(impl TextBufferInterface + ?Sized)) -> String {
+    text_and_color_layers((screen.width(), screen.height()), |x, y| buffer_cell(screen, x, y))
+}
+
+/// Compare a dump against a golden file, panicking with both versions if they differ or if the golden file is missing.
+/// Golden files are only written when the update variable is set to 1
+pub fn assert_matches_golden(actual: &str, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+
+    if matches!(std::env::var(UPDATE_SNAPSHOTS_VAR).as_deref(), Ok("1")) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, actual).unwrap();
+        return;
+    }
+
+    if !path.exists() {
+        panic!("snapshot {} is missing, rerun with {}=1 to create it\n--- actual\n{}", path.display(), UPDATE_SNAPSHOTS_VAR, actual);
+    }
+
+    let expected = std::fs::read_to_string(path).unwrap();
+
+    if expected != actual {
+        panic!("snapshot {} does not match, rerun with {}=1 to update it\n--- expected\n{}--- actual\n{}", path.display(), UPDATE_SNAPSHOTS_VAR, expected, actual);
+    }
+}
+
+/// Assert that the text of a buffer, view or widget buffer matches a golden file
+pub fn assert_snapshot(screen: &(impl TextBufferInterface + ?Sized), path: impl AsRef<Path>) {
+    assert_matches_golden(&text_snapshot(screen), path);
+}
+
+/// Assert that the text, colors and attributes of a buffer, view or widget buffer match a golden file
+pub fn assert_color_snapshot(screen: &(impl TextBufferInterface + ?Sized), path: impl AsRef<Path>) {
+    assert_matches_golden(&color_snapshot(screen), path);
+}
@@ -1,135 +1,229 @@
 use std::fmt::Display;
 
-use crate::screen::{TextBufferPos, CharacterColor, TextAlign, TextBufferInterface, TextBufferRect, Drawable, TextFormatting, VGAChar};
+use crate::screen::{Cell, CellStyle, CharacterColor, TextAlign, TextBufferInterface, TextBufferPos, TextBufferRect, VGAChar};
 
-use super::{SelectionMenu, UIElement};
+use super::{draw_box_styled, UIElement};
 
-pub struct DisplayBox {
-    menu: SelectionMenu<T>,
-    pos: TextBufferPos,
-    settings: MenuSettings,
-    last_rect: Option<TextBufferRect>,
-    dirty: bool,
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayBoxSettings {
+    pub value_style: CellStyle,
+    pub border_style: CellStyle,
+    pub label_align: TextAlign,
+    pub text_align: TextAlign,
+    pub ellipsis: bool
 }
 
-impl<T: Drawable<TextFormatting>> GraphicalMenu<T> {
-    pub fn new(elements: Vec<T>, pos: TextBufferPos, settings: MenuSettings) -> Self {
+impl DisplayBoxSettings {
+    pub const fn new() -> Self {
         Self {
-            menu: SelectionMenu::new(elements),
-            pos,
-            settings,
-            last_rect: None,
-            dirty: true,
+            value_style: CellStyle::new(CharacterColor::White, CharacterColor::Black),
+            border_style: CellStyle::new(CharacterColor::BrightWhite, CharacterColor::Black),
+            label_align: TextAlign::Left,
+            text_align: TextAlign::Left,
+            ellipsis: true
         }
     }
 
-    pub fn draw(&mut self, screen: &mut impl TextBufferInterface) {
-        let mut running_rect: Option<TextBufferRect> = None;
+    pub const fn value_style(mut self, style: CellStyle) -> Self {
+        self.value_style = style;
+        self
+    }
 
-        let pos = if !self.settings.fix_selected {
-            self.pos
-        }
-        else {
-            let i = self.menu.force_index() as isize;
-            (self.pos.x - self.settings.menu_step.0 * i,
-            self.pos.y - self.settings.menu_step.1 * i).into()
-        };
+    pub const fn border_style(mut self, style: CellStyle) -> Self {
+        self.border_style = style;
+        self
+    }
+
+    pub const fn label_align(mut self, align: TextAlign) -> Self {
+        self.label_align = align;
+        self
+    }
+
+    pub const fn align(mut self, align: TextAlign) -> Self {
+        self.text_align = align;
+        self
+    }
+
+    /// Cut values which do not fit off without marking them with "..."
+    pub const fn clip(mut self) -> Self {
+        self.ellipsis = false;
+        self
+    }
+}
+
+impl std::default::Default for DisplayBoxSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        for (index, (is_selected, value)) in self.menu.elements_flagged().enumerate() {
-            if is_selected || !self.settings.hide_others {
-                let color = if is_selected { self.settings.selected_color } else { self.settings.unselected_color };
-                let position = (pos.x + index as isize * self.settings.menu_step.0 as isize,
-                                    pos.y + index as isize * self.settings.menu_step.1 as isize).into();
-
-                let rect = value.draw(screen, position, &(color, self.settings.text_align).into());
-
-                if let Some(running_rect) = &mut running_rect {
-                    *running_rect = running_rect.union(&rect);
-                }
-                else {
-                    running_rect = Some(rect);
-                }
-            }
+/// A limit which changes the style of the value when it is crossed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold<T> {
+    /// Applies when the value is strictly below the limit
+    Below(T, CellStyle),
+    /// Applies when the value is at or above the limit
+    AtLeast(T, CellStyle)
+}
+
+impl<T: PartialOrd> Threshold<T> {
+    /// Get the style of the threshold if it applies to the value
+    fn style_for(&self, value: &T) -> Option<CellStyle> {
+        match self {
+            Threshold::Below(limit, style) if value < limit => Some(*style),
+            Threshold::AtLeast(limit, style) if value >= limit => Some(*style),
+            _ => None
         }
+    }
+}
 
-        self.last_rect = running_rect;
+/// A labelled, bordered panel showing a single read only value, which is only redrawn when the value changes
+pub struct DisplayBox<T> {
+    rect: TextBufferRect,
+    label: String,
+    value: T,
+    settings: DisplayBoxSettings,
+    thresholds: Vec<Threshold<T>>,
+    last_rect: Option<TextBufferRect>,
+    dirty: bool
+}
+
+impl<T: Display + PartialOrd> DisplayBox<T> {
+    /// Construct a new display box, the rect includes the border and must be at least 8 cells wide and 3 tall
+    pub fn new(rect: TextBufferRect, label: &str, value: T, settings: DisplayBoxSettings) -> Self {
+        Self {
+            rect,
+            label: label.to_string(),
+            value,
+            settings,
+            thresholds: Vec::new(),
+            last_rect: None,
+            dirty: true
+        }
     }
 
-    pub fn prev(&mut self) {
-        let w = self.settings.wrapping;
-        self.mut_menu().prev(w);
+    /// Add a threshold, when several apply the one added first is used
+    pub fn threshold(mut self, threshold: Threshold<T>) -> Self {
+        self.thresholds.push(threshold);
+        self
     }
 
-    pub fn next(&mut self) {
-        let w = self.settings.wrapping;
-        self.mut_menu().next(w);
+    pub fn value(&self) -> &T {
+        &self.value
     }
 
-    pub fn menu(&self) -> &SelectionMenu<T> {
-        &self.menu
+    /// Set the value, the box is only marked for redrawing if it changed
+    pub fn set_value(&mut self, value: T) {
+        if value != self.value {
+            self.value = value;
+            self.dirty = true;
+        }
     }
 
-    pub fn mut_menu(&mut self) -> &mut SelectionMenu<T> {
-        self.dirty = true;
-        &mut self.menu
+    pub fn label(&self) -> &str {
+        &self.label
     }
 
-    pub fn clear_last(&self, screen: &mut impl TextBufferInterface) {
-        if let Some(rect) = self.last_rect {
-            screen.clear_rect(rect);
+    pub fn set_label(&mut self, label: &str) {
+        if label != self.label {
+            self.label = label.to_string();
+            self.dirty = true;
         }
     }
 
-    pub fn take_dirty(&mut self) -> bool {
-        let dirty = self.dirty;
-        self.dirty = false;
-        dirty
+    pub fn rect(&self) -> TextBufferRect {
+        self.rect
     }
 
-    pub fn settings(&self) -> &MenuSettings {
+    pub fn settings(&self) -> &DisplayBoxSettings {
         &self.settings
     }
 
-    pub fn mut_settings(&mut self) -> &mut MenuSettings {
+    pub fn mut_settings(&mut self) -> &mut DisplayBoxSettings {
         self.dirty = true;
         &mut self.settings
     }
 
-    pub fn set_pos(&mut self, pos: TextBufferPos) {
-        self.pos = pos;
+    /// Get the style the value is currently drawn in
+    pub fn value_style(&self) -> CellStyle {
+        self.thresholds.iter()
+            .find_map(|t| t.style_for(&self.value))
+            .unwrap_or(self.settings.value_style)
     }
-}
 
-impl<T: Drawable<TextFormatting>> UIElement for GraphicalMenu<T> {
-    fn take_dirty(&mut self) -> bool {
-        self.take_dirty()
+    /// Get the value as it is drawn, truncated to the interior of the box
+    pub fn value_text(&self) -> String {
+        let width = self.rect.interior().width;
+        let text = self.value.to_string();
+
+        if text.chars().count() <= width {
+            text
+        }
+        else if self.settings.ellipsis && width > 3 {
+            text.chars().take(width - 3).chain("...".chars()).collect()
+        }
+        else {
+            text.chars().take(width).collect()
+        }
     }
 
-    fn ui_draw(&mut self, screen: &mut impl TextBufferInterface) {
-        self.draw(screen)
+    pub fn draw(&mut self, screen: &mut (impl TextBufferInterface + ?Sized)) {
+        let interior = self.rect.interior();
+
+        draw_box_styled(self.rect, screen, Some((&self.label, self.settings.label_align)), self.settings.border_style);
+        screen.clear_rect(interior);
+
+        let style = self.value_style();
+        let cells: Vec<Cell> = self.value_text().chars()
+            .map(|c| Cell::new(VGAChar::from_char(c).unwrap_or(VGAChar(b'?')), style))
+            .collect();
+
+        let y = interior.y + (interior.height.saturating_sub(1) / 2) as isize;
+        let x = match self.settings.text_align {
+            TextAlign::Left => interior.x,
+            TextAlign::Center => interior.x + ((interior.width - cells.len()) / 2) as isize,
+            TextAlign::Right => interior.right() - cells.len() as isize
+        };
+
+        screen.write_data(TextBufferPos { x, y }, &cells);
+        self.last_rect = Some(self.rect);
     }
 
-    fn clear_last(&self, screen: &mut impl TextBufferInterface) {
-        self.clear_last(screen)
+    pub fn clear_last(&self, screen: &mut (impl TextBufferInterface + ?Sized)) {
+        if let Some(rect) = self.last_rect {
+            screen.clear_rect(rect);
+        }
     }
-}
 
-impl<T: Display> Drawable<TextFormatting> for GraphicalMenu<T> {
-    fn draw(&self, screen: &mut impl TextBufferInterface, pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
-        let mut total = Vec::new();
+    pub fn take_dirty(&mut self) -> bool {
+        let dirty = self.dirty;
+        self.dirty = false;
+        dirty
+    }
+}
 
-        total.push((VGAChar(b'<'), settings.color));
-        total.push((VGAChar(b' '), settings.color));
+impl<T: Display + PartialOrd> UIElement for DisplayBox<T> {
+    fn take_dirty(&mut self) -> bool {
+        self.take_dirty()
+    }
 
-        let s = self.menu.force_selected().to_string();
+    fn ui_draw(&mut self, screen: &mut dyn TextBufferInterface) {
+        self.draw(screen)
+    }
 
-        for c in s.chars() {
-            total.push((c.try_into().unwrap(), settings.color));
+    fn clear_last(&self, screen: &mut dyn TextBufferInterface) {
+        // The box draws over its whole area, so only a box which has moved needs clearing
+        if self.last_rect != Some(self.rect) {
+            self.clear_last(screen)
         }
+    }
 
-        total.push((VGAChar(b' '), settings.color));
-        total.push((VGAChar(b'>'), settings.color));
+    fn set_rect(&mut self, rect: TextBufferRect) {
+        self.rect = rect;
+        self.dirty = true;
+    }
 
-        screen.write_data_align(pos, &total, settings.alignment)
+    fn hit_rect(&self) -> Option<TextBufferRect> {
+        self.last_rect
     }
-}
\ No newline at end of file
+}
@@ -1,18 +1,29 @@
-use crate::screen::{TextBufferRect, TextBufferInterface, TextAlign};
+use crate::screen::{TextBufferRect, TextBufferInterface, TextAlign, CellStyle, CharacterColor};
 
-pub fn draw_box(rect: TextBufferRect, screen: &mut impl TextBufferInterface, name: Option<(&str, TextAlign)>) {
-    assert!(rect.width >= 8);
+pub fn draw_box(rect: TextBufferRect, screen: &mut (impl TextBufferInterface + ?Sized), name: Option<(&str, TextAlign)>) {
+    draw_box_styled(rect, screen, name, CharacterColor::BrightWhite.into())
+}
+
+/// Draw a box with the border and title in the given style, used to highlight the focused pane.
+/// Titles which do not fit are cut short with "...", boxes too narrow for that are drawn without a title
+pub fn draw_box_styled(rect: TextBufferRect, screen: &mut (impl TextBufferInterface + ?Sized), name: Option<(&str, TextAlign)>, style: CellStyle) {
+    if rect.width < 2 || rect.height < 2 {
+        return;
+    }
+
+    let name = name.filter(|(name, _)| rect.width >= 8 || name.chars().count() + 4 <= rect.width);
 
     let top_s = if let Some((name, align)) = name {
-        let name = if name.len() + 4 <= rect.width {
+        let name = if name.chars().count() + 4 <= rect.width {
             name.to_string()
         }
         else {
-            format!("{}...", &name[..rect.width - 7])
+            format!("{}...", name.chars().take(rect.width - 7).collect::<String>())
         };
+        let name_len = name.chars().count();
 
-        let left = (rect.width - 4 - name.len() + 1) / 2;
-        let right = (rect.width - 4 - name.len()) / 2;
+        let left = (rect.width - 4 - name_len).div_ceil(2);
+        let right = (rect.width - 4 - name_len) / 2;
 
         let mut s = String::from("┌");
 
@@ -69,11 +80,11 @@ pub fn draw_box(rect: TextBufferRect, screen: &mut impl TextBufferInterface, nam
         s
     };
 
-    screen.write_string((rect.x, rect.y).into(), &top_s, crate::screen::CharacterColor::BrightWhite).unwrap();
-    screen.write_string((rect.x, rect.bottom() - 1).into(), &bottom_s, crate::screen::CharacterColor::BrightWhite).unwrap();
+    screen.write_string((rect.x, rect.y).into(), &top_s, style).unwrap();
+    screen.write_string((rect.x, rect.bottom() - 1).into(), &bottom_s, style).unwrap();
 
     for i in rect.y + 1..rect.bottom() - 1 {
-        screen.write_string((rect.x, i).into(), "│", crate::screen::CharacterColor::BrightWhite).unwrap();
-        screen.write_string((rect.right() - 1, i).into(), "│", crate::screen::CharacterColor::BrightWhite).unwrap();
+        screen.write_string((rect.x, i).into(), "│", style).unwrap();
+        screen.write_string((rect.right() - 1, i).into(), "│", style).unwrap();
     }
 }
\ No newline at end of file
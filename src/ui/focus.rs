@@ -0,0 +1,281 @@
+use crate::input::{InputEvent, Key, MouseEvent, MouseEventKind};
+use crate::screen::{CellStyle, CharacterColor, TextAlign, TextBufferPos, TextBufferRect, TextBufferScreen, TextView};
+
+use super::{draw_box_styled, UIElement};
+
+/// A bordered area of the screen which an element is drawn inside of
+struct Pane {
+    rect: TextBufferRect,
+    title: Option<(String, TextAlign)>
+}
+
+/// An element owned by a focus manager
+struct FocusEntry {
+    element: Box<dyn UIElement>,
+    pane: Option<Pane>
+}
+
+impl FocusEntry {
+    /// Get the position on the screen the element is drawn relative to
+    fn origin(&self) -> TextBufferPos {
+        match &self.pane {
+            Some(pane) => TextBufferPos { x: pane.rect.interior().x, y: pane.rect.interior().y },
+            None => TextBufferPos { x: 0, y: 0 }
+        }
+    }
+
+    /// Get a mouse event relative to the area the element is drawn in
+    fn local_event(&self, event: &MouseEvent) -> MouseEvent {
+        event.relative_to(self.origin())
+    }
+
+    /// Check if a position on the screen is over the element
+    fn hit_test(&self, pos: TextBufferPos) -> bool {
+        let inside_pane = match &self.pane {
+            Some(pane) => pane.rect.interior().contains_point(pos.x, pos.y),
+            None => true
+        };
+        let origin = self.origin();
+        inside_pane && self.element.hit_test(TextBufferPos { x: pos.x - origin.x, y: pos.y - origin.y })
+    }
+}
+
+/// Owns a list of UI elements, tracks which one has focus and routes input to it. Tab and Shift-Tab move focus between focusable elements
+pub struct FocusManager {
+    entries: Vec<FocusEntry>,
+    focused: Option<usize>,
+    border_style: CellStyle,
+    focused_border_style: CellStyle,
+    redraw_borders: bool,
+    redraw_all: bool
+}
+
+impl FocusManager {
+    /// Construct a new, empty focus manager
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            focused: None,
+            border_style: CharacterColor::BrightWhite.into(),
+            focused_border_style: CharacterColor::Yellow.into(),
+            redraw_borders: true,
+            redraw_all: true
+        }
+    }
+
+    /// Set the style of the borders around unfocused panes
+    pub const fn border_style(mut self, style: CellStyle) -> Self {
+        self.border_style = style;
+        self
+    }
+
+    /// Set the style of the border around the focused pane
+    pub const fn focused_border_style(mut self, style: CellStyle) -> Self {
+        self.focused_border_style = style;
+        self
+    }
+
+    fn push_entry(&mut self, element: Box<dyn UIElement>, pane: Option<Pane>) -> usize {
+        self.entries.push(FocusEntry { element, pane });
+        self.redraw_all = true;
+
+        let index = self.entries.len() - 1;
+        if self.focused.is_none() && self.entries[index].element.focusable() {
+            self.focus(index);
+        }
+
+        index
+    }
+
+    /// Add an element which is drawn directly to the screen, returns its index. The first focusable element added takes focus
+    pub fn push(&mut self, element: impl UIElement + 'static) -> usize {
+        self.push_entry(Box::new(element), None)
+    }
+
+    /// Add an element drawn inside of a box with an optional title, returns its index. The element's rect is set to the interior of the box
+    pub fn push_pane(&mut self, element: impl UIElement + 'static, rect: TextBufferRect, title: Option<(&str, TextAlign)>) -> usize {
+        let mut element = Box::new(element);
+        element.set_rect(TextBufferRect::new(0, 0, rect.interior().width, rect.interior().height));
+
+        self.push_entry(element, Some(Pane { rect, title: title.map(|(s, a)| (s.to_string(), a)) }))
+    }
+
+    /// Move the box around an element, the screen should be cleared before the next draw
+    pub fn set_pane_rect(&mut self, index: usize, rect: TextBufferRect) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            if let Some(pane) = &mut entry.pane {
+                pane.rect = rect;
+                entry.element.set_rect(TextBufferRect::new(0, 0, rect.interior().width, rect.interior().height));
+                self.redraw_all = true;
+            }
+        }
+    }
+
+    /// Get the number of elements
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the element at the given index
+    pub fn element(&self, index: usize) -> Option<&dyn UIElement> {
+        self.entries.get(index).map(|e| e.element.as_ref())
+    }
+
+    /// Get a mutable reference to the element at the given index
+    pub fn element_mut(&mut self, index: usize) -> Option<&mut (dyn UIElement + 'static)> {
+        self.entries.get_mut(index).map(|e| e.element.as_mut())
+    }
+
+    /// Get the index of the focused element
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    /// Give focus to the element at the given index, elements which are not focusable are ignored
+    pub fn focus(&mut self, index: usize) {
+        if self.focused == Some(index) || !self.entries.get(index).map(|e| e.element.focusable()).unwrap_or(false) {
+            return;
+        }
+
+        if let Some(entry) = self.focused.and_then(|i| self.entries.get_mut(i)) {
+            entry.element.set_focused(false);
+        }
+
+        self.entries[index].element.set_focused(true);
+        self.focused = Some(index);
+        self.redraw_borders = true;
+    }
+
+    /// Move focus forwards or backwards to the next focusable element, wrapping around the ends of the list
+    fn cycle_focus(&mut self, forwards: bool) {
+        let count = self.entries.len();
+        let start = self.focused.unwrap_or(if forwards { count - 1 } else { 0 });
+
+        for step in 1..=count {
+            let index = if forwards { (start + step) % count } else { (start + count - step) % count };
+
+            if self.entries[index].element.focusable() {
+                self.focus(index);
+                return;
+            }
+        }
+    }
+
+    /// Move focus to the next focusable element
+    pub fn focus_next(&mut self) {
+        if !self.entries.is_empty() {
+            self.cycle_focus(true);
+        }
+    }
+
+    /// Move focus to the previous focusable element
+    pub fn focus_prev(&mut self) {
+        if !self.entries.is_empty() {
+            self.cycle_focus(false);
+        }
+    }
+
+    /// Get the index of the element under a position on the screen, elements added later are on top
+    pub fn element_at(&self, pos: TextBufferPos) -> Option<usize> {
+        self.entries.iter().rposition(|entry| entry.hit_test(pos))
+    }
+
+    /// Route a mouse event. Movement goes to every element so they can update hover highlights, presses focus the element under the mouse,
+    /// the wheel goes to the element under the mouse and drags and releases go to the focused element
+    fn handle_mouse(&mut self, event: &MouseEvent) -> bool {
+        let target = match event.kind {
+            MouseEventKind::Moved => {
+                let mut used = false;
+                for entry in self.entries.iter_mut() {
+                    let local = entry.local_event(event);
+                    used |= entry.element.on_event(&InputEvent::Mouse(local));
+                }
+                return used;
+            }
+            MouseEventKind::Pressed(_) => {
+                let target = self.element_at(event.pos);
+                if let Some(index) = target {
+                    self.focus(index);
+                }
+                target
+            }
+            MouseEventKind::Wheel { .. } => self.element_at(event.pos),
+            MouseEventKind::Dragged(_) | MouseEventKind::Released(_) => self.focused
+        };
+
+        match target {
+            Some(index) => {
+                let entry = &mut self.entries[index];
+                let local = entry.local_event(event);
+                entry.element.on_event(&InputEvent::Mouse(local))
+            }
+            None => false
+        }
+    }
+
+    /// Handle an input event, Tab and Shift-Tab move focus, mouse events go to the element under the mouse and anything else is given to the focused element. Returns true if the event was used
+    pub fn handle_event(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::Mouse(mouse) => self.handle_mouse(mouse),
+            InputEvent::KeyDown { key: Key::Tab, modifiers, .. } if !modifiers.ctrl && !modifiers.alt => {
+                if modifiers.shift {
+                    self.focus_prev();
+                }
+                else {
+                    self.focus_next();
+                }
+                true
+            }
+            _ => match self.focused {
+                Some(index) => self.entries[index].element.on_event(event),
+                None => false
+            }
+        }
+    }
+
+    /// Draw every element and border on the next call to draw, used after the screen has been cleared or resized
+    pub fn invalidate(&mut self) {
+        self.redraw_all = true;
+    }
+
+    /// Draw the borders which changed focus and every element which has changed
+    pub fn draw(&mut self, screen: &mut TextBufferScreen) {
+        let redraw_all = self.redraw_all;
+        let redraw_borders = self.redraw_borders || redraw_all;
+
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            let Some(pane) = &entry.pane else {
+                if entry.element.take_dirty() || redraw_all {
+                    entry.element.clear_last(screen);
+                    entry.element.ui_draw(screen);
+                }
+                continue;
+            };
+
+            if redraw_borders {
+                let style = if self.focused == Some(index) { self.focused_border_style } else { self.border_style };
+                draw_box_styled(pane.rect, screen, pane.title.as_ref().map(|(s, a)| (s.as_str(), *a)), style);
+            }
+
+            if entry.element.take_dirty() || redraw_all {
+                let mut view = TextView::new(pane.rect.interior(), screen);
+                entry.element.clear_last(&mut view);
+                entry.element.ui_draw(&mut view);
+            }
+        }
+
+        self.redraw_all = false;
+        self.redraw_borders = false;
+    }
+}
+
+impl std::default::Default for FocusManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
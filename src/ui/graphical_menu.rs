@@ -1,12 +1,14 @@
 use std::fmt::Display;
 
-use crate::screen::{TextBufferPos, CharacterColor, TextAlign, TextBufferInterface, TextBufferRect, Drawable, TextFormatting, VGAChar};
+use crate::input::{InputEvent, Key, MouseButton, MouseEvent, MouseEventKind};
+use crate::screen::{TextBufferPos, CharacterColor, Color, CellStyle, CellAttributes, Cell, TextAlign, TextBufferInterface, TextBufferRect, Drawable, TextFormatting, VGAChar};
 
 use super::{SelectionMenu, UIElement};
 
 pub struct MenuSettings {
-    pub unselected_color: CharacterColor,
-    pub selected_color: CharacterColor,
+    pub unselected_style: CellStyle,
+    pub selected_style: CellStyle,
+    pub hover_style: Option<CellStyle>,
     pub text_align: TextAlign,
     pub menu_step: (isize, isize),
     pub fix_selected: bool,
@@ -15,11 +17,18 @@ pub struct MenuSettings {
     pub hide_others: bool
 }
 
+impl Default for MenuSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MenuSettings {
     pub const fn new() -> Self {
         Self {
-            unselected_color: CharacterColor::White,
-            selected_color: CharacterColor::Yellow,
+            unselected_style: CellStyle::new(CharacterColor::White, CharacterColor::Black),
+            selected_style: CellStyle::new(CharacterColor::Yellow, CharacterColor::Black),
+            hover_style: Some(CellStyle::new(CharacterColor::LightCyan, CharacterColor::Black)),
             text_align: TextAlign::Left,
             menu_step: (0, 1),
             fix_selected: false,
@@ -50,8 +59,37 @@ impl MenuSettings {
     }
 
     pub const fn color_scheme(mut self, colors: (CharacterColor, CharacterColor)) -> Self {
-        self.selected_color = colors.1;
-        self.unselected_color = colors.0;
+        self.selected_style.foreground = Color::Named(colors.1);
+        self.unselected_style.foreground = Color::Named(colors.0);
+        self
+    }
+
+    pub const fn background_scheme(mut self, colors: (CharacterColor, CharacterColor)) -> Self {
+        self.selected_style.background = Color::Named(colors.1);
+        self.unselected_style.background = Color::Named(colors.0);
+        self
+    }
+
+    pub const fn style_scheme(mut self, styles: (CellStyle, CellStyle)) -> Self {
+        self.selected_style = styles.1;
+        self.unselected_style = styles.0;
+        self
+    }
+
+    pub const fn attribute_scheme(mut self, attributes: (CellAttributes, CellAttributes)) -> Self {
+        self.selected_style.attributes = attributes.1;
+        self.unselected_style.attributes = attributes.0;
+        self
+    }
+
+    /// Set the style of unselected items under the mouse, None turns hover highlighting off
+    pub const fn hover_style(mut self, style: Option<CellStyle>) -> Self {
+        self.hover_style = style;
+        self
+    }
+
+    pub const fn reverse_selection(mut self) -> Self {
+        self.selected_style = self.unselected_style.with(CellAttributes::REVERSE);
         self
     }
 
@@ -76,6 +114,8 @@ pub struct GraphicalMenu<T> {
     pos: TextBufferPos,
     settings: MenuSettings,
     last_rect: Option<TextBufferRect>,
+    item_rects: Vec<(usize, TextBufferRect)>,
+    hovered: Option<usize>,
     dirty: bool,
 }
 
@@ -86,12 +126,15 @@ impl<T: Drawable<TextFormatting>> GraphicalMenu<T> {
             pos,
             settings,
             last_rect: None,
+            item_rects: Vec::new(),
+            hovered: None,
             dirty: true,
         }
     }
 
-    pub fn draw(&mut self, screen: &mut impl TextBufferInterface) {
+    pub fn draw(&mut self, screen: &mut (impl TextBufferInterface + ?Sized)) {
         let mut running_rect: Option<TextBufferRect> = None;
+        self.item_rects.clear();
 
         let pos = if !self.settings.fix_selected {
             self.pos
@@ -104,11 +147,20 @@ impl<T: Drawable<TextFormatting>> GraphicalMenu<T> {
 
         for (index, (is_selected, value)) in self.menu.elements_flagged().enumerate() {
             if is_selected || !self.settings.hide_others {
-                let color = if is_selected { self.settings.selected_color } else { self.settings.unselected_color };
-                let position = (pos.x + index as isize * self.settings.menu_step.0 as isize,
-                                    pos.y + index as isize * self.settings.menu_step.1 as isize).into();
+                let style = if is_selected {
+                    self.settings.selected_style
+                }
+                else if self.hovered == Some(index) {
+                    self.settings.hover_style.unwrap_or(self.settings.unselected_style)
+                }
+                else {
+                    self.settings.unselected_style
+                };
+                let position = (pos.x + index as isize * self.settings.menu_step.0,
+                                    pos.y + index as isize * self.settings.menu_step.1).into();
 
-                let rect = value.draw(screen, position, &(color, self.settings.text_align).into());
+                let rect = value.draw(screen, position, &(style, self.settings.text_align).into());
+                self.item_rects.push((index, rect));
 
                 if let Some(running_rect) = &mut running_rect {
                     *running_rect = running_rect.union(&rect);
@@ -122,6 +174,16 @@ impl<T: Drawable<TextFormatting>> GraphicalMenu<T> {
         self.last_rect = running_rect;
     }
 
+    /// Get the index of the item drawn at a position
+    pub fn item_at(&self, pos: TextBufferPos) -> Option<usize> {
+        self.item_rects.iter().find(|(_, rect)| rect.contains_point(pos.x, pos.y)).map(|(index, _)| *index)
+    }
+
+    /// Get the index of the item under the mouse
+    pub fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+
     pub fn prev(&mut self) {
         let w = self.settings.wrapping;
         self.mut_menu().prev(w);
@@ -141,7 +203,7 @@ impl<T: Drawable<TextFormatting>> GraphicalMenu<T> {
         &mut self.menu
     }
 
-    pub fn clear_last(&self, screen: &mut impl TextBufferInterface) {
+    pub fn clear_last(&self, screen: &mut (impl TextBufferInterface + ?Sized)) {
         if let Some(rect) = self.last_rect {
             screen.clear_rect(rect);
         }
@@ -172,30 +234,60 @@ impl<T: Drawable<TextFormatting>> UIElement for GraphicalMenu<T> {
         self.take_dirty()
     }
 
-    fn ui_draw(&mut self, screen: &mut impl TextBufferInterface) {
+    fn ui_draw(&mut self, screen: &mut dyn TextBufferInterface) {
         self.draw(screen)
     }
 
-    fn clear_last(&self, screen: &mut impl TextBufferInterface) {
+    fn clear_last(&self, screen: &mut dyn TextBufferInterface) {
         self.clear_last(screen)
     }
+
+    fn on_event(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::KeyDown { key: Key::Up | Key::Left, .. } => self.prev(),
+            InputEvent::KeyDown { key: Key::Down | Key::Right, .. } => self.next(),
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Moved, pos }) => {
+                let hovered = self.item_at(*pos);
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    self.dirty = true;
+                }
+                return hovered.is_some();
+            }
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Pressed(MouseButton::Left), pos }) => match self.item_at(*pos) {
+                Some(index) => self.mut_menu().select(index),
+                None => return false
+            },
+            _ => return false
+        }
+
+        true
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn hit_rect(&self) -> Option<TextBufferRect> {
+        self.last_rect
+    }
 }
 
 impl<T: Display> Drawable<TextFormatting> for GraphicalMenu<T> {
-    fn draw(&self, screen: &mut impl TextBufferInterface, pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
+    fn draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
         let mut total = Vec::new();
 
-        total.push((VGAChar(b'<'), settings.color));
-        total.push((VGAChar(b' '), settings.color));
+        total.push(Cell::new(VGAChar(b'<'), settings.style));
+        total.push(Cell::new(VGAChar(b' '), settings.style));
 
         let s = self.menu.force_selected().to_string();
 
         for c in s.chars() {
-            total.push((c.try_into().unwrap(), settings.color));
+            total.push(Cell::new(c.try_into().unwrap(), settings.style));
         }
 
-        total.push((VGAChar(b' '), settings.color));
-        total.push((VGAChar(b'>'), settings.color));
+        total.push(Cell::new(VGAChar(b' '), settings.style));
+        total.push(Cell::new(VGAChar(b'>'), settings.style));
 
         screen.write_data_align(pos, &total, settings.alignment)
     }
@@ -1,8 +1,52 @@
-use crate::screen::TextBufferInterface;
+use crate::input::InputEvent;
+use crate::screen::{TextBufferInterface, TextBufferPos, TextBufferRect};
 
-/// Shared behavior for UIElements
+/// Shared behavior for UIElements, the trait is object safe so screens can own lists of `Box<dyn UIElement>`
 pub trait UIElement {
+    /// Get and clear the flag marking that the element has changed since it was last drawn
     fn take_dirty(&mut self) -> bool;
-    fn ui_draw(&mut self, screen: &mut impl TextBufferInterface);
-    fn clear_last(&self, screen: &mut impl TextBufferInterface);
+
+    /// Draw the element to the text buffer interface
+    fn ui_draw(&mut self, screen: &mut dyn TextBufferInterface);
+
+    /// Clear the area the element covered when it was last drawn
+    fn clear_last(&self, screen: &mut dyn TextBufferInterface);
+
+    /// Clear and redraw the element if it has changed, returns true if it was drawn
+    fn refresh(&mut self, screen: &mut dyn TextBufferInterface) -> bool {
+        if self.take_dirty() {
+            self.clear_last(screen);
+            self.ui_draw(screen);
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    /// Handle an input event while the element has focus, returns true if the event was used
+    fn on_event(&mut self, _event: &InputEvent) -> bool {
+        false
+    }
+
+    /// Check if the element can take focus
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    /// Called when the element gains or loses focus
+    fn set_focused(&mut self, _focused: bool) {}
+
+    /// Move or resize the element within the area it is drawn to
+    fn set_rect(&mut self, _rect: TextBufferRect) {}
+
+    /// Get the area the element covered when it was last drawn, used to find the element under the mouse
+    fn hit_rect(&self) -> Option<TextBufferRect> {
+        None
+    }
+
+    /// Check if a position is inside the area the element was last drawn to
+    fn hit_test(&self, pos: TextBufferPos) -> bool {
+        self.hit_rect().is_some_and(|rect| rect.contains_point(pos.x, pos.y))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use crate::screen::{TextBufferInterface, TextBufferRect};
+
+/// How much space a part of a layout takes along the direction it is split in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    /// Exactly this many cells
+    Fixed(usize),
+    /// This percentage of the space being split
+    Percent(usize),
+    /// At least this many cells, growing to share the leftover space
+    Min(usize),
+    /// Shares the leftover space, but never more than this many cells
+    Max(usize),
+    /// Shares the leftover space in proportion to its weight
+    Fill(usize)
+}
+
+impl Constraint {
+    /// Get the size taken before leftover space is shared out
+    const fn base(&self, length: usize) -> usize {
+        match self {
+            Constraint::Fixed(n) | Constraint::Min(n) => *n,
+            Constraint::Percent(p) => length * *p / 100,
+            Constraint::Max(_) | Constraint::Fill(_) => 0
+        }
+    }
+
+    /// Get the share of the leftover space this takes
+    const fn weight(&self) -> usize {
+        match self {
+            Constraint::Fixed(_) | Constraint::Percent(_) => 0,
+            Constraint::Min(_) | Constraint::Max(_) => 1,
+            Constraint::Fill(weight) => *weight
+        }
+    }
+
+    /// Get the most cells this can take
+    const fn cap(&self) -> usize {
+        match self {
+            Constraint::Max(n) => *n,
+            _ => usize::MAX
+        }
+    }
+}
+
+/// Direction a layout is split in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Parts are placed left to right
+    Horizontal,
+    /// Parts are placed top to bottom
+    Vertical
+}
+
+/// What fills one part of a layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LayoutNode {
+    Pane(String),
+    Split(Layout),
+    Gap
+}
+
+/// A tree of splits which divides a rectangle into named panes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    direction: Direction,
+    children: Vec<(Constraint, LayoutNode)>
+}
+
+impl Layout {
+    /// Construct a new layout split in the given direction
+    pub const fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            children: Vec::new()
+        }
+    }
+
+    /// Construct a new layout with parts placed left to right
+    pub const fn horizontal() -> Self {
+        Self::new(Direction::Horizontal)
+    }
+
+    /// Construct a new layout with parts placed top to bottom
+    pub const fn vertical() -> Self {
+        Self::new(Direction::Vertical)
+    }
+
+    /// Add a named pane
+    pub fn pane(mut self, constraint: Constraint, name: &str) -> Self {
+        self.children.push((constraint, LayoutNode::Pane(name.to_string())));
+        self
+    }
+
+    /// Add a nested layout which splits its part further
+    pub fn split(mut self, constraint: Constraint, layout: Layout) -> Self {
+        self.children.push((constraint, LayoutNode::Split(layout)));
+        self
+    }
+
+    /// Add empty space
+    pub fn gap(mut self, constraint: Constraint) -> Self {
+        self.children.push((constraint, LayoutNode::Gap));
+        self
+    }
+
+    /// Split a rectangle into one rectangle per part of this layout, without descending into nested layouts
+    pub fn split_rect(&self, rect: TextBufferRect) -> Vec<TextBufferRect> {
+        let length = match self.direction {
+            Direction::Horizontal => rect.width,
+            Direction::Vertical => rect.height
+        };
+
+        let constraints: Vec<Constraint> = self.children.iter().map(|(c, _)| *c).collect();
+        let mut offset = 0;
+
+        solve(&constraints, length).into_iter().map(|size| {
+            let part = match self.direction {
+                Direction::Horizontal => TextBufferRect::new(rect.x + offset as isize, rect.y, size, rect.height),
+                Direction::Vertical => TextBufferRect::new(rect.x, rect.y + offset as isize, rect.width, size)
+            };
+            offset += size;
+            part
+        }).collect()
+    }
+
+    /// Compute the rectangle of every named pane in the layout
+    pub fn compute(&self, rect: TextBufferRect) -> HashMap<String, TextBufferRect> {
+        let mut result = HashMap::new();
+        self.compute_into(rect, &mut result);
+        result
+    }
+
+    fn compute_into(&self, rect: TextBufferRect, result: &mut HashMap<String, TextBufferRect>) {
+        for ((_, node), part) in self.children.iter().zip(self.split_rect(rect)) {
+            match node {
+                LayoutNode::Pane(name) => { result.insert(name.clone(), part); }
+                LayoutNode::Split(layout) => layout.compute_into(part, result),
+                LayoutNode::Gap => ()
+            }
+        }
+    }
+}
+
+/// Find the size of each part of a split of the given length. Fixed sizes are given out first in order, then the leftover space is shared by weight with max constraints capped
+fn solve(constraints: &[Constraint], length: usize) -> Vec<usize> {
+    let mut sizes = Vec::with_capacity(constraints.len());
+    let mut remaining = length;
+
+    for constraint in constraints {
+        let size = constraint.base(length).min(remaining);
+        sizes.push(size);
+        remaining -= size;
+    }
+
+    let mut open: Vec<usize> = (0..constraints.len()).filter(|i| constraints[*i].weight() > 0).collect();
+
+    while remaining > 0 && !open.is_empty() {
+        let total_weight: usize = open.iter().map(|i| constraints[*i].weight()).sum();
+        let share = |i: usize| remaining * constraints[i].weight() / total_weight;
+
+        // Parts which would pass their cap are fixed at it, and the rest share what is left
+        let capped: Vec<usize> = open.iter().copied().filter(|i| sizes[*i] + share(*i) > constraints[*i].cap()).collect();
+        if !capped.is_empty() {
+            for i in capped {
+                let grow = constraints[i].cap().saturating_sub(sizes[i]);
+                sizes[i] += grow;
+                remaining -= grow;
+                open.retain(|j| *j != i);
+            }
+            continue;
+        }
+
+        let shares: Vec<usize> = open.iter().map(|i| share(*i)).collect();
+        for (i, share) in open.iter().zip(shares) {
+            sizes[*i] += share;
+            remaining -= share;
+        }
+
+        // Cells lost to rounding go to the earliest parts
+        for i in open.iter() {
+            if remaining == 0 {
+                break;
+            }
+            if sizes[*i] < constraints[*i].cap() {
+                sizes[*i] += 1;
+                remaining -= 1;
+            }
+        }
+
+        break;
+    }
+
+    sizes
+}
+
+/// A layout bound to a screen, which recomputes its panes whenever the size of the screen changes
+#[derive(Debug, Clone)]
+pub struct ScreenLayout {
+    layout: Layout,
+    size: Option<(usize, usize)>,
+    rects: HashMap<String, TextBufferRect>
+}
+
+impl ScreenLayout {
+    /// Construct a new screen layout, the panes are computed on the first update
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            size: None,
+            rects: HashMap::new()
+        }
+    }
+
+    /// Recompute the panes if the size of the screen has changed, returns true if they were recomputed
+    pub fn update(&mut self, screen: &(impl TextBufferInterface + ?Sized)) -> bool {
+        let size = (screen.width(), screen.height());
+
+        if self.size == Some(size) {
+            return false;
+        }
+
+        self.size = Some(size);
+        self.rects = self.layout.compute(TextBufferRect::new(0, 0, size.0, size.1));
+        true
+    }
+
+    /// Get the layout the panes are computed from
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Replace the layout, the panes are recomputed on the next update
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.size = None;
+    }
+
+    /// Get the rectangle of a named pane as of the last update
+    pub fn rect(&self, name: &str) -> Option<TextBufferRect> {
+        self.rects.get(name).copied()
+    }
+
+    /// Get the rectangles of all of the named panes as of the last update
+    pub fn rects(&self) -> &HashMap<String, TextBufferRect> {
+        &self.rects
+    }
+}
+
+impl std::ops::Index<&str> for ScreenLayout {
+    type Output = TextBufferRect;
+
+    fn index(&self, name: &str) -> &Self::Output {
+        &self.rects[name]
+    }
+}
@@ -49,6 +49,13 @@ impl<T> SelectionMenu<T> {
         }
     }
 
+    /// Select the element at the given index, indices past the end are ignored
+    pub fn select(&mut self, index: usize) {
+        if index < self.elements.len() {
+            self.selected_index = Some(index);
+        }
+    }
+
     /// Deselect the current element in the vector
     pub fn deselect(&mut self) {
         if let Some(previous_index) = self.selected_index.take() {
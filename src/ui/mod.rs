@@ -1,9 +1,18 @@
+pub mod display_box;
+pub use display_box::*;
+
 pub mod draw_box;
 pub use draw_box::*;
 
+pub mod layout;
+pub use layout::*;
+
 pub mod menu;
 pub use menu::*;
 
+pub mod focus;
+pub use focus::*;
+
 pub mod graphical_menu;
 pub use graphical_menu::*;
 
@@ -13,6 +22,12 @@ pub use interface::*;
 pub mod scrollbox;
 pub use scrollbox::*;
 
+pub mod text_area;
+pub use text_area::*;
+
+pub mod text_input;
+pub use text_input::*;
+
 pub mod value_spinbox;
 pub use value_spinbox::*;
 
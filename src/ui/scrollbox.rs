@@ -1,16 +1,25 @@
-use crate::screen::{TextBufferRect, VGAChar, CharacterColor, TextBufferInterface};
+use crate::input::{InputEvent, Key, MouseButton, MouseEvent, MouseEventKind};
+use crate::screen::{TextBufferRect, TextBufferPos, Cell, CellStyle, CharacterColor, TextBufferInterface, VGAChar};
 
 use super::UIElement;
 
+/// Rows or columns scrolled for each step of the mouse wheel
+pub const SCROLL_WHEEL_STEP: isize = 3;
+
+/// A viewport onto a larger buffer of cells, which can be written to like any other text buffer
 pub struct ScrollBox {
     pub rect: TextBufferRect,
     width: usize,
     height: usize,
-    internal_buffer: Vec<(VGAChar, CharacterColor)>,
+    internal_buffer: Vec<Cell>,
     dirty_regions: Vec<TextBufferRect>,
 
     scroll: (isize, isize),
-    bounded: bool
+    bounded: bool,
+    scrollbars: (bool, bool),
+    scrollbar_style: CellStyle,
+    smooth: bool,
+    target: Option<(isize, isize)>
 }
 
 impl ScrollBox {
@@ -19,40 +28,243 @@ impl ScrollBox {
             rect,
             width: size.0,
             height: size.1,
-            internal_buffer: vec![(VGAChar(b' '), CharacterColor::Gray); size.0 * size.1],
+            internal_buffer: vec![Cell::blank(); size.0 * size.1],
             dirty_regions: vec![TextBufferRect::new(0, 0, size.0, size.1)],
             scroll: (0, 0),
-            bounded: false
+            bounded: false,
+            scrollbars: (false, false),
+            scrollbar_style: CellStyle::new(CharacterColor::White, CharacterColor::Black),
+            smooth: false,
+            target: None
         }
     }
 
-    pub fn scroll_horizontal(&mut self, amt: isize) {
+    fn invalidate(&mut self) {
+        self.dirty_regions = vec![TextBufferRect::new(0, 0, self.width, self.height)];
+    }
+
+    /// Resize the scrolled content, clearing it
+    pub fn resize(&mut self, size: (usize, usize)) {
+        self.width = size.0;
+        self.height = size.1;
+        self.internal_buffer = vec![Cell::blank(); size.0 * size.1];
+        self.invalidate();
+    }
+
+    /// Get the current scroll offset, which is the position in the content shown at the top left of the viewport
+    pub fn scroll(&self) -> (isize, isize) {
+        self.scroll
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.bounded
+    }
+
+    /// Keep the viewport inside the content instead of allowing it to scroll past the edges
+    pub fn set_bounded(&mut self, bounded: bool) {
+        self.bounded = bounded;
+        self.scroll_to(self.scroll.0, self.scroll.1);
+    }
+
+    /// Show scrollbars along the right and bottom edges, they take space from the viewport
+    pub fn set_scrollbars(&mut self, vertical: bool, horizontal: bool) {
+        self.scrollbars = (vertical, horizontal);
+        self.invalidate();
+    }
+
+    pub fn set_scrollbar_style(&mut self, style: CellStyle) {
+        self.scrollbar_style = style;
+        self.invalidate();
+    }
+
+    /// Move page scrolling across several draws instead of jumping straight to the new page
+    pub fn set_smooth_scrolling(&mut self, smooth: bool) {
+        self.smooth = smooth;
+    }
+
+    /// Get the part of the rect the content is shown in, which excludes the scrollbars
+    pub fn viewport(&self) -> TextBufferRect {
+        let width = self.rect.width.saturating_sub(self.scrollbars.0 as usize);
+        let height = self.rect.height.saturating_sub(self.scrollbars.1 as usize);
+        TextBufferRect::new(self.rect.x, self.rect.y, width, height)
+    }
+
+    /// Get the furthest the viewport can scroll while bounded
+    fn max_scroll(&self) -> (isize, isize) {
+        let viewport = self.viewport();
+        (self.width.saturating_sub(viewport.width) as isize, self.height.saturating_sub(viewport.height) as isize)
+    }
+
+    /// Scroll so the given position in the content is at the top left of the viewport
+    pub fn scroll_to(&mut self, x: isize, y: isize) {
         let backup = self.scroll;
-        if !self.bounded {
-            self.scroll.0 += amt;
-        }
-        else {
-            self.scroll.0 = (self.scroll.0 + amt).max(0).min(self.width as isize - self.rect.width as isize);
+        self.scroll = (x, y);
+
+        if self.bounded {
+            let max = self.max_scroll();
+            self.scroll = (x.clamp(0, max.0), y.clamp(0, max.1));
         }
 
-        if self.scroll != backup {  
-            self.dirty_regions = vec![TextBufferRect::new(0, 0, self.width, self.height)];
+        self.target = None;
+        if self.scroll != backup {
+            self.invalidate();
         }
     }
 
+    /// Scroll as little as possible to bring a rect of the content into view
+    pub fn scroll_into_view(&mut self, rect: TextBufferRect) {
+        let viewport = self.viewport();
+        let axis = |scroll: isize, start: isize, length: usize, view: usize| {
+            if start < scroll {
+                start
+            }
+            else if start + length as isize > scroll + view as isize {
+                (start + length as isize - view as isize).min(start)
+            }
+            else {
+                scroll
+            }
+        };
+
+        let x = axis(self.scroll.0, rect.x, rect.width, viewport.width);
+        let y = axis(self.scroll.1, rect.y, rect.height, viewport.height);
+        self.scroll_to(x, y);
+    }
+
+    /// Shift the content horizontally, a positive amount moves the content right so columns further left come into view.
+    /// This is the opposite direction to scroll_to and scroll_vertical, which move the view rather than the content
+    pub fn scroll_horizontal(&mut self, amt: isize) {
+        self.scroll_by(-amt, 0);
+    }
+
     pub fn scroll_vertical(&mut self, amt: isize) {
-        let backup = self.scroll;
-        if !self.bounded {
-            self.scroll.1 += amt;
+        self.scroll_to(self.scroll.0, self.scroll.1 + amt);
+    }
+
+    /// Move the view by an amount, a positive amount shows content further right or down
+    fn scroll_by(&mut self, x: isize, y: isize) {
+        self.scroll_to(self.scroll.0 + x, self.scroll.1 + y);
+    }
+
+    /// Scroll by a number of pages, keeping one row of the previous page in view
+    pub fn scroll_pages(&mut self, pages: isize) {
+        let page = (self.viewport().height as isize - 1).max(1);
+        let (x, mut y) = self.target.unwrap_or(self.scroll);
+        y += page * pages;
+
+        if self.bounded {
+            y = y.clamp(0, self.max_scroll().1);
+        }
+
+        if self.smooth {
+            self.target = Some((x, y));
+            self.invalidate();
         }
         else {
-            self.scroll.1 = (self.scroll.1 + amt).max(0).min(self.height as isize - self.rect.height as isize);
+            self.scroll_to(x, y);
         }
+    }
 
-        if self.scroll != backup {  
-            self.dirty_regions = vec![TextBufferRect::new(0, 0, self.width, self.height)];
+    /// Returns true while a smooth page scroll is still moving
+    pub fn is_scrolling(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Move a smooth page scroll one step closer to where it is going, covering half of the remaining distance
+    fn step_smooth(&mut self) {
+        if let Some((x, y)) = self.target {
+            let remaining = y - self.scroll.1;
+            let step = if remaining.abs() <= 1 { remaining } else { remaining / 2 };
+
+            self.scroll = (x, self.scroll.1 + step);
+            if self.scroll.1 == y {
+                self.target = None;
+            }
+            self.invalidate();
+        }
+    }
+
+    /// Get the first cell and length of the scrollbar thumb within a track, given the scroll offset, the viewport size and the content size
+    fn thumb(track: usize, scroll: isize, view: usize, content: usize) -> (usize, usize) {
+        if content <= view || track == 0 {
+            return (0, track);
+        }
+
+        let length = (track * view / content).clamp(1, track);
+        let max_scroll = (content - view) as isize;
+        let scroll = scroll.clamp(0, max_scroll) as usize;
+        let start = (scroll * (track - length) + max_scroll as usize / 2) / max_scroll as usize;
+
+        (start, length)
+    }
+
+    fn draw_scrollbars(&self, screen: &mut dyn TextBufferInterface) {
+        let viewport = self.viewport();
+        let style = self.scrollbar_style;
+        let cell = |c: u8| Cell::new(VGAChar(c), style);
+
+        if self.scrollbars.0 && viewport.height >= 2 {
+            let x = viewport.right();
+            let track = viewport.height - 2;
+            let (start, length) = Self::thumb(track, self.scroll.1, viewport.height, self.height);
+
+            screen.write_data((x, viewport.y).into(), &[cell(0x1E)]);
+            for i in 0..track {
+                let c = if i >= start && i < start + length { 0xB2 } else { 0xB0 };
+                screen.write_data((x, viewport.y + 1 + i as isize).into(), &[cell(c)]);
+            }
+            screen.write_data((x, viewport.bottom() - 1).into(), &[cell(0x1F)]);
+        }
+
+        if self.scrollbars.1 && viewport.width >= 2 {
+            let y = viewport.bottom();
+            let track = viewport.width - 2;
+            let (start, length) = Self::thumb(track, self.scroll.0, viewport.width, self.width);
+
+            let mut bar = vec![cell(0x11)];
+            bar.extend((0..track).map(|i| cell(if i >= start && i < start + length { 0xB2 } else { 0xB0 })));
+            bar.push(cell(0x10));
+            screen.write_data((viewport.x, y).into(), &bar);
         }
     }
+
+    /// Handle a mouse click at a position on the screen, the arrows scroll by one and the track either side of the thumb scrolls by a page. Returns true if the click was on a scrollbar
+    pub fn click(&mut self, pos: TextBufferPos) -> bool {
+        let viewport = self.viewport();
+
+        if self.scrollbars.0 && pos.x == viewport.right() && pos.y >= viewport.y && pos.y < viewport.bottom() {
+            let track = viewport.height.saturating_sub(2);
+            let (start, length) = Self::thumb(track, self.scroll.1, viewport.height, self.height);
+            let i = pos.y - viewport.y - 1;
+
+            match i {
+                -1 => self.scroll_vertical(-1),
+                i if i == track as isize => self.scroll_vertical(1),
+                i if i < start as isize => self.scroll_pages(-1),
+                i if i >= (start + length) as isize => self.scroll_pages(1),
+                _ => ()
+            }
+            return true;
+        }
+
+        if self.scrollbars.1 && pos.y == viewport.bottom() && pos.x >= viewport.x && pos.x < viewport.right() {
+            let track = viewport.width.saturating_sub(2);
+            let (start, length) = Self::thumb(track, self.scroll.0, viewport.width, self.width);
+            let page = (viewport.width as isize - 1).max(1);
+            let i = pos.x - viewport.x - 1;
+
+            match i {
+                -1 => self.scroll_by(-1, 0),
+                i if i == track as isize => self.scroll_by(1, 0),
+                i if i < start as isize => self.scroll_by(-page, 0),
+                i if i >= (start + length) as isize => self.scroll_by(page, 0),
+                _ => ()
+            }
+            return true;
+        }
+
+        false
+    }
 }
 
 impl UIElement for ScrollBox {
@@ -60,31 +272,69 @@ impl UIElement for ScrollBox {
         !self.dirty_regions.is_empty()
     }
 
-    fn ui_draw(&mut self, screen: &mut impl crate::screen::TextBufferInterface) {
+    fn ui_draw(&mut self, screen: &mut dyn crate::screen::TextBufferInterface) {
+        self.step_smooth();
         self.dirty_regions.clear();
 
         screen.clear_rect(self.rect);
 
-        self.scroll_horizontal(0);
-        self.scroll_vertical(0);
+        let viewport = self.viewport();
+        let first = self.scroll.0.max(0);
+        let last = (self.scroll.0 + viewport.width as isize).min(self.width as isize);
 
-        for (y_index, y) in (self.rect.y..self.rect.bottom()).enumerate() {
+        for (y_index, y) in (viewport.y..viewport.bottom()).enumerate() {
             let y_index = y_index as isize + self.scroll.1;
-            if let Some(i) = self.index_of((0, y_index as isize).into())
-            {
-                if self.scroll.0 >= 0 {
-                    screen.write_data((self.rect.x + self.scroll.0, y).into(), &self.internal_buffer[i..(i+self.rect.width-self.scroll.0 as usize).min(i + self.width)]);
-                }
-                else {
-                    screen.write_data((self.rect.x, y).into(), &self.internal_buffer[(i + (-self.scroll.0) as usize)..(i+self.rect.width+(-self.scroll.0) as usize).min(i + self.width)]);
+            if let Some(i) = self.index_of((0, y_index).into()) {
+                if first < last {
+                    let row = &self.internal_buffer[i + first as usize..i + last as usize];
+                    screen.write_data((viewport.x + first - self.scroll.0, y).into(), row);
                 }
             }
         }
+
+        self.draw_scrollbars(screen);
+
+        // A smooth scroll keeps the box dirty until it arrives
+        if self.target.is_some() {
+            self.invalidate();
+        }
     }
 
-    fn clear_last(&self, _screen: &mut impl crate::screen::TextBufferInterface) {
+    fn clear_last(&self, _screen: &mut dyn crate::screen::TextBufferInterface) {
         // Nothing needs to be done here since refresh already draws over the entire screen
     }
+
+    fn on_event(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::KeyDown { key: Key::Left, .. } => self.scroll_by(-1, 0),
+            InputEvent::KeyDown { key: Key::Right, .. } => self.scroll_by(1, 0),
+            InputEvent::KeyDown { key: Key::Up, .. } => self.scroll_vertical(-1),
+            InputEvent::KeyDown { key: Key::Down, .. } => self.scroll_vertical(1),
+            InputEvent::KeyDown { key: Key::PageUp, .. } => self.scroll_pages(-1),
+            InputEvent::KeyDown { key: Key::PageDown, .. } => self.scroll_pages(1),
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Wheel { x, y }, pos }) if self.hit_test(*pos) => {
+                self.scroll_by(*x as isize * SCROLL_WHEEL_STEP, -*y as isize * SCROLL_WHEEL_STEP);
+            }
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Pressed(MouseButton::Left), pos }) => return self.click(*pos),
+            _ => return false
+        }
+
+        true
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_rect(&mut self, rect: TextBufferRect) {
+        self.rect = rect;
+        self.scroll_to(self.scroll.0, self.scroll.1);
+        self.invalidate();
+    }
+
+    fn hit_rect(&self) -> Option<TextBufferRect> {
+        Some(self.rect)
+    }
 }
 
 impl TextBufferInterface for ScrollBox {
@@ -101,11 +351,11 @@ impl TextBufferInterface for ScrollBox {
         }
     }
 
-    fn inner_data_buffer(&self) -> &[(VGAChar, CharacterColor)] {
+    fn inner_data_buffer(&self) -> &[Cell] {
         &self.internal_buffer
     }
 
-    fn inner_data_buffer_mut(&mut self) -> &mut [(VGAChar, CharacterColor)] {
+    fn inner_data_buffer_mut(&mut self) -> &mut [Cell] {
         &mut self.internal_buffer
     }
 
@@ -0,0 +1,587 @@
+use crate::input::{InputEvent, Key, KeyModifiers};
+use crate::screen::{Cell, CellAttributes, CellStyle, CharacterColor, TextBufferInterface, TextBufferRect, VGAChar};
+
+use super::{ScrollBox, TextInput, UIElement};
+
+/// Most edits which can be undone, older ones are dropped
+pub const UNDO_LIMIT: usize = 256;
+
+/// Position in a text area as a line and a character index within it
+pub type TextAreaPos = (usize, usize);
+
+#[derive(Debug, Clone, Copy)]
+pub struct TextAreaSettings {
+    pub style: CellStyle,
+    pub selection_style: CellStyle,
+    pub line_number_style: CellStyle,
+    pub wrap: bool,
+    pub line_numbers: bool
+}
+
+impl TextAreaSettings {
+    pub const fn new() -> Self {
+        Self {
+            style: CellStyle::new(CharacterColor::White, CharacterColor::Black),
+            selection_style: CellStyle::new(CharacterColor::Black, CharacterColor::Cyan),
+            line_number_style: CellStyle::new(CharacterColor::Gray, CharacterColor::Black),
+            wrap: false,
+            line_numbers: false
+        }
+    }
+
+    pub const fn style(mut self, style: CellStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub const fn selection_style(mut self, style: CellStyle) -> Self {
+        self.selection_style = style;
+        self
+    }
+
+    pub const fn line_number_style(mut self, style: CellStyle) -> Self {
+        self.line_number_style = style;
+        self
+    }
+
+    /// Wrap long lines at word boundaries instead of scrolling horizontally
+    pub const fn wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    /// Show the number of each line in a gutter on the left
+    pub const fn line_numbers(mut self) -> Self {
+        self.line_numbers = true;
+        self
+    }
+}
+
+impl std::default::Default for TextAreaSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A part of a line shown on one row of the view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Row {
+    line: usize,
+    start: usize,
+    end: usize,
+    last: bool
+}
+
+/// State restored by undo and redo
+#[derive(Debug, Clone)]
+struct Snapshot {
+    lines: Vec<Vec<VGAChar>>,
+    cursor: TextAreaPos
+}
+
+/// A multi-line text editor drawn through a scroll box, the text is stored as code page 437 characters
+pub struct TextArea {
+    rect: TextBufferRect,
+    lines: Vec<Vec<VGAChar>>,
+    cursor: TextAreaPos,
+    anchor: Option<TextAreaPos>,
+    goal_column: Option<usize>,
+    view: (usize, usize),
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    typing: bool,
+    scrollbox: ScrollBox,
+    settings: TextAreaSettings,
+    focused: bool,
+    dirty: bool
+}
+
+impl TextArea {
+    pub fn new(rect: TextBufferRect, settings: TextAreaSettings) -> Self {
+        Self {
+            rect,
+            lines: vec![Vec::new()],
+            cursor: (0, 0),
+            anchor: None,
+            goal_column: None,
+            view: (0, 0),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            typing: false,
+            scrollbox: ScrollBox::new(rect, (1, 1)),
+            settings,
+            focused: false,
+            dirty: true
+        }
+    }
+
+    /// Convert text to code page 437, characters outside of it are replaced with '?' and other control characters are dropped
+    fn encode(text: &str) -> Vec<Vec<VGAChar>> {
+        text.split('\n').map(|line| {
+            line.chars()
+                .filter(|c| !c.is_control() || *c == '\t')
+                .map(|c| if c == '\t' { VGAChar(b' ') } else { VGAChar::from_char(c).unwrap_or(VGAChar(b'?')) })
+                .collect()
+        }).collect()
+    }
+
+    /// Get the text with lines separated by '\n'
+    pub fn text(&self) -> String {
+        let lines: Vec<String> = self.lines.iter().map(|line| line.iter().map(|c| c.to_char()).collect()).collect();
+        lines.join("\n")
+    }
+
+    /// Replace the text, clearing the undo history. Characters outside of code page 437 are replaced with '?'
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = Self::encode(text);
+        self.undo.clear();
+        self.redo.clear();
+        self.anchor = None;
+        self.set_cursor((0, 0), false);
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn line(&self, index: usize) -> Option<String> {
+        self.lines.get(index).map(|line| line.iter().map(|c| c.to_char()).collect())
+    }
+
+    pub fn cursor(&self) -> TextAreaPos {
+        self.cursor
+    }
+
+    /// Move the cursor, keeping the selection anchored where it was if extending
+    pub fn set_cursor(&mut self, pos: TextAreaPos, extend_selection: bool) {
+        if extend_selection {
+            self.anchor.get_or_insert(self.cursor);
+        }
+        else {
+            self.anchor = None;
+        }
+
+        let line = pos.0.min(self.lines.len() - 1);
+        self.cursor = (line, pos.1.min(self.lines[line].len()));
+        if self.anchor == Some(self.cursor) {
+            self.anchor = None;
+        }
+
+        self.goal_column = None;
+        self.typing = false;
+        self.dirty = true;
+    }
+
+    /// Get the start and end of the selection, if anything is selected
+    pub fn selection(&self) -> Option<(TextAreaPos, TextAreaPos)> {
+        self.anchor.map(|anchor| (anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    pub fn selected_text(&self) -> String {
+        match self.selection() {
+            Some((start, end)) => {
+                let lines: Vec<String> = (start.0..=end.0).map(|line| {
+                    let from = if line == start.0 { start.1 } else { 0 };
+                    let to = if line == end.0 { end.1 } else { self.lines[line].len() };
+                    self.lines[line][from..to].iter().map(|c| c.to_char()).collect()
+                }).collect();
+                lines.join("\n")
+            }
+            None => String::new()
+        }
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = None;
+        self.cursor = (0, 0);
+        self.set_cursor((self.lines.len() - 1, usize::MAX), true);
+    }
+
+    /// Insert text at the cursor, replacing the selection. Characters outside of code page 437 are replaced with '?'
+    pub fn insert_str(&mut self, text: &str) {
+        let typing = !text.contains('\n') && self.selection().is_none();
+        if !(typing && self.typing) {
+            self.push_undo();
+        }
+
+        self.remove_selection();
+
+        let mut pieces = Self::encode(text);
+        let (line, column) = self.cursor;
+        let tail = self.lines[line].split_off(column);
+        let last = pieces.len() - 1;
+        let cursor = (line + last, pieces[last].len() + if last == 0 { column } else { 0 });
+
+        pieces[last].extend(tail);
+        let mut pieces = pieces.into_iter();
+        self.lines[line].extend(pieces.next().unwrap());
+        self.lines.splice(line + 1..line + 1, pieces);
+
+        self.set_cursor(cursor, false);
+        self.typing = typing;
+    }
+
+    /// Delete the selection, or the character before the cursor if nothing is selected
+    pub fn backspace(&mut self) {
+        if self.selection().is_none() {
+            match self.cursor {
+                (0, 0) => return,
+                (line, 0) => self.anchor = Some((line - 1, self.lines[line - 1].len())),
+                (line, column) => self.anchor = Some((line, column - 1))
+            }
+        }
+
+        self.push_undo();
+        self.remove_selection();
+        self.set_cursor(self.cursor, false);
+    }
+
+    /// Delete the selection, or the character after the cursor if nothing is selected
+    pub fn delete(&mut self) {
+        if self.selection().is_none() {
+            let (line, column) = self.cursor;
+            if column < self.lines[line].len() {
+                self.anchor = Some((line, column + 1));
+            }
+            else if line + 1 < self.lines.len() {
+                self.anchor = Some((line + 1, 0));
+            }
+            else {
+                return;
+            }
+        }
+
+        self.push_undo();
+        self.remove_selection();
+        self.set_cursor(self.cursor, false);
+    }
+
+    fn remove_selection(&mut self) {
+        if let Some((start, end)) = self.selection() {
+            let tail = self.lines[end.0].split_off(end.1);
+            self.lines[start.0].truncate(start.1);
+            self.lines[start.0].extend(tail);
+            self.lines.drain(start.0 + 1..=end.0);
+
+            self.anchor = None;
+            self.cursor = start;
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot { lines: self.lines.clone(), cursor: self.cursor }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.lines = snapshot.lines;
+        self.anchor = None;
+        self.set_cursor(snapshot.cursor, false);
+    }
+
+    fn push_undo(&mut self) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+
+        self.undo.push(self.snapshot());
+        self.redo.clear();
+    }
+
+    /// Undo the last edit, consecutive typed characters are undone together. Returns false if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(snapshot) => {
+                self.redo.push(self.snapshot());
+                self.restore(snapshot);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Redo the last undone edit, returns false if there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(snapshot) => {
+                self.undo.push(self.snapshot());
+                self.restore(snapshot);
+                true
+            }
+            None => false
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Get the width of the line number gutter, including the space after the numbers
+    fn gutter_width(&self) -> usize {
+        if self.settings.line_numbers {
+            self.lines.len().to_string().len() + 1
+        }
+        else {
+            0
+        }
+    }
+
+    /// Get the rect the text itself is drawn in
+    fn text_rect(&self) -> TextBufferRect {
+        let gutter = self.gutter_width().min(self.rect.width);
+        TextBufferRect::new(self.rect.x + gutter as isize, self.rect.y, self.rect.width - gutter, self.rect.height)
+    }
+
+    /// Split the lines into the rows they are shown on. Wrapped lines break after the last space which fits, or mid-word if there is none
+    fn rows(&self) -> Vec<Row> {
+        let width = self.text_rect().width;
+        let mut rows = Vec::new();
+
+        for (index, line) in self.lines.iter().enumerate() {
+            let mut start = 0;
+
+            if self.settings.wrap && width > 0 {
+                while line.len() - start > width {
+                    let end = (start + 1..=start + width).rev()
+                        .find(|i| line[i - 1] == VGAChar(b' '))
+                        .unwrap_or(start + width);
+                    rows.push(Row { line: index, start, end, last: false });
+                    start = end;
+                }
+
+                // A full row leaves no room for the cursor after it, so it gets an empty row below
+                if line.len() - start == width {
+                    rows.push(Row { line: index, start, end: line.len(), last: false });
+                    start = line.len();
+                }
+            }
+
+            rows.push(Row { line: index, start, end: line.len(), last: true });
+        }
+
+        rows
+    }
+
+    /// Get the row the cursor is on and its column within that row
+    fn cursor_row(&self, rows: &[Row]) -> (usize, usize) {
+        let (line, column) = self.cursor;
+        let index = rows.iter().position(|r| r.line == line && (column < r.end || r.last)).unwrap_or(0);
+        (index, column - rows[index].start)
+    }
+
+    /// Move the cursor by a number of rows, staying as close as possible to the column it started in
+    fn move_rows(&mut self, amount: isize, extend_selection: bool) {
+        let rows = self.rows();
+        let (index, column) = self.cursor_row(&rows);
+        let goal = self.goal_column.unwrap_or(column);
+
+        let target = rows[(index as isize + amount).clamp(0, rows.len() as isize - 1) as usize];
+        let length = if target.last { target.end - target.start } else { (target.end - target.start).saturating_sub(1) };
+
+        self.set_cursor((target.line, target.start + goal.min(length)), extend_selection);
+        self.goal_column = Some(goal);
+    }
+
+    fn move_left(&mut self, extend_selection: bool) {
+        match self.cursor {
+            (0, 0) => self.set_cursor((0, 0), extend_selection),
+            (line, 0) => self.set_cursor((line - 1, self.lines[line - 1].len()), extend_selection),
+            (line, column) => self.set_cursor((line, column - 1), extend_selection)
+        }
+    }
+
+    fn move_right(&mut self, extend_selection: bool) {
+        let (line, column) = self.cursor;
+        if column == self.lines[line].len() && line + 1 < self.lines.len() {
+            self.set_cursor((line + 1, 0), extend_selection);
+        }
+        else {
+            self.set_cursor((line, column + 1), extend_selection);
+        }
+    }
+
+    /// Get the first row and column shown, after scrolling to keep the cursor in view
+    pub fn view(&self) -> (usize, usize) {
+        self.view
+    }
+
+    fn scroll_to_cursor(&mut self, rows: &[Row]) {
+        let text_rect = self.text_rect();
+        let (row, column) = self.cursor_row(rows);
+
+        if row < self.view.0 {
+            self.view.0 = row;
+        }
+        else if row >= self.view.0 + text_rect.height {
+            self.view.0 = row + 1 - text_rect.height.max(1);
+        }
+
+        if self.settings.wrap || column < self.view.1 {
+            self.view.1 = if self.settings.wrap { 0 } else { column };
+        }
+        else if column >= self.view.1 + text_rect.width {
+            self.view.1 = column + 1 - text_rect.width.max(1);
+        }
+    }
+
+    pub fn rect(&self) -> TextBufferRect {
+        self.rect
+    }
+
+    pub fn settings(&self) -> &TextAreaSettings {
+        &self.settings
+    }
+
+    pub fn mut_settings(&mut self) -> &mut TextAreaSettings {
+        self.dirty = true;
+        &mut self.settings
+    }
+
+    /// Draw the text area, this goes through the scroll box so the screen has to be a trait object
+    pub fn draw(&mut self, screen: &mut dyn TextBufferInterface) {
+        let rows = self.rows();
+        self.scroll_to_cursor(&rows);
+
+        let text_rect = self.text_rect();
+        let content_width = if self.settings.wrap {
+            text_rect.width
+        }
+        else {
+            self.lines.iter().map(|line| line.len()).max().unwrap_or(0) + 1
+        };
+
+        // The whole text is laid out into the scroll box, which then shows the part in view
+        self.scrollbox.rect = text_rect;
+        self.scrollbox.resize((content_width.max(1), rows.len()));
+
+        let selection = self.selection();
+        for (y, row) in rows.iter().enumerate() {
+            let cells: Vec<Cell> = (row.start..row.end).map(|column| {
+                let style = match selection {
+                    Some((start, end)) if (row.line, column) >= start && (row.line, column) < end => self.settings.selection_style,
+                    _ => self.settings.style
+                };
+                Cell::new(self.lines[row.line][column], style)
+            }).collect();
+
+            self.scrollbox.write_data((0, y as isize).into(), &cells);
+        }
+
+        if self.focused {
+            let (row, column) = self.cursor_row(&rows);
+            let (line, index) = self.cursor;
+            let character = self.lines[line].get(index).copied().unwrap_or(VGAChar(b' '));
+            let style = match selection {
+                Some((start, end)) if self.cursor >= start && self.cursor < end => self.settings.selection_style,
+                _ => self.settings.style
+            };
+
+            self.scrollbox.write_data((column as isize, row as isize).into(), &[Cell::new(character, style.with(CellAttributes::REVERSE))]);
+        }
+
+        self.scrollbox.scroll_to(self.view.1 as isize, self.view.0 as isize);
+        self.scrollbox.ui_draw(screen);
+
+        let gutter = self.gutter_width().min(self.rect.width);
+        if gutter > 0 {
+            let gutter_rect = TextBufferRect::new(self.rect.x, self.rect.y, gutter, self.rect.height);
+            screen.fill_rect(gutter_rect, Cell::new(VGAChar(b' '), self.settings.line_number_style));
+
+            for (y, row) in rows.iter().skip(self.view.0).take(self.rect.height).enumerate() {
+                if row.start == 0 {
+                    let number = format!("{:>width$}", row.line + 1, width = gutter - 1);
+                    let text: Vec<VGAChar> = number.chars().filter_map(VGAChar::from_char).collect();
+                    screen.write_text((self.rect.x, self.rect.y + y as isize).into(), &text, self.settings.line_number_style);
+                }
+            }
+        }
+    }
+
+    pub fn clear_last(&self, screen: &mut (impl TextBufferInterface + ?Sized)) {
+        screen.clear_rect(self.rect);
+    }
+
+    pub fn take_dirty(&mut self) -> bool {
+        let dirty = self.dirty;
+        self.dirty = false;
+        dirty
+    }
+}
+
+impl UIElement for TextArea {
+    fn take_dirty(&mut self) -> bool {
+        self.take_dirty()
+    }
+
+    fn ui_draw(&mut self, screen: &mut dyn TextBufferInterface) {
+        self.draw(screen)
+    }
+
+    fn clear_last(&self, _screen: &mut dyn TextBufferInterface) {
+        // Nothing needs to be done here since the scroll box clears its whole rect when drawn
+    }
+
+    fn on_event(&mut self, event: &InputEvent) -> bool {
+        let (key, modifiers) = match event {
+            InputEvent::Text(text) => {
+                let text: String = text.chars().filter(|c| TextInput::accepts_char(*c)).collect();
+                if text.is_empty() {
+                    return false;
+                }
+                self.insert_str(&text);
+                return true;
+            }
+            InputEvent::KeyDown { key, modifiers, .. } => (*key, *modifiers),
+            _ => return false
+        };
+
+        let extend = modifiers.shift;
+        let page = self.rect.height.max(1) as isize;
+
+        match key {
+            Key::Left => self.move_left(extend),
+            Key::Right => self.move_right(extend),
+            Key::Up => self.move_rows(-1, extend),
+            Key::Down => self.move_rows(1, extend),
+            Key::PageUp => self.move_rows(-page, extend),
+            Key::PageDown => self.move_rows(page, extend),
+            Key::Home if modifiers.ctrl => self.set_cursor((0, 0), extend),
+            Key::End if modifiers.ctrl => self.set_cursor((self.lines.len() - 1, usize::MAX), extend),
+            Key::Home => self.set_cursor((self.cursor.0, 0), extend),
+            Key::End => self.set_cursor((self.cursor.0, usize::MAX), extend),
+            Key::Enter => self.insert_str("\n"),
+            Key::Backspace => self.backspace(),
+            Key::Delete => self.delete(),
+            Key::Char('a') if modifiers == KeyModifiers::CTRL => self.select_all(),
+            Key::Char('z') if modifiers == KeyModifiers::CTRL => { self.undo(); }
+            Key::Char('y') if modifiers == KeyModifiers::CTRL => { self.redo(); }
+            Key::Char('z') if modifiers.ctrl && modifiers.shift => { self.redo(); }
+            // Characters arrive as text events, the key presses which produced them are only swallowed
+            Key::Char(_) if !modifiers.ctrl && !modifiers.alt => (),
+            _ => return false
+        }
+
+        true
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.dirty = true;
+    }
+
+    fn set_rect(&mut self, rect: TextBufferRect) {
+        self.rect = rect;
+        self.dirty = true;
+    }
+
+    fn hit_rect(&self) -> Option<TextBufferRect> {
+        Some(self.rect)
+    }
+}
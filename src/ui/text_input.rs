@@ -0,0 +1,382 @@
+use crate::input::{InputEvent, Key, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use crate::screen::{Cell, CellAttributes, CellStyle, CharacterColor, TextBufferInterface, TextBufferPos, TextBufferRect, VGAChar};
+
+use super::UIElement;
+
+/// Check run on the text of an input after every edit
+pub type TextValidator = Box<dyn Fn(&str) -> bool>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TextInputSettings {
+    pub style: CellStyle,
+    pub focused_style: CellStyle,
+    pub placeholder_style: CellStyle,
+    pub selection_style: CellStyle,
+    pub max_length: Option<usize>
+}
+
+impl TextInputSettings {
+    pub const fn new() -> Self {
+        Self {
+            style: CellStyle::new(CharacterColor::White, CharacterColor::Black),
+            focused_style: CellStyle::new(CharacterColor::BrightWhite, CharacterColor::Black),
+            placeholder_style: CellStyle::new(CharacterColor::Gray, CharacterColor::Black),
+            selection_style: CellStyle::new(CharacterColor::Black, CharacterColor::Cyan),
+            max_length: None
+        }
+    }
+
+    pub const fn style(mut self, style: CellStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub const fn focused_style(mut self, style: CellStyle) -> Self {
+        self.focused_style = style;
+        self
+    }
+
+    pub const fn placeholder_style(mut self, style: CellStyle) -> Self {
+        self.placeholder_style = style;
+        self
+    }
+
+    pub const fn selection_style(mut self, style: CellStyle) -> Self {
+        self.selection_style = style;
+        self
+    }
+
+    /// Limit the number of characters which can be entered
+    pub const fn max_length(mut self, length: usize) -> Self {
+        self.max_length = Some(length);
+        self
+    }
+}
+
+impl std::default::Default for TextInputSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A single line of editable text, scrolled horizontally to keep the cursor in view when the text is wider than its rect
+pub struct TextInput {
+    rect: TextBufferRect,
+    text: Vec<char>,
+    cursor: usize,
+    anchor: Option<usize>,
+    scroll: usize,
+    placeholder: String,
+    validator: Option<TextValidator>,
+    settings: TextInputSettings,
+    focused: bool,
+    last_rect: Option<TextBufferRect>,
+    dirty: bool
+}
+
+impl TextInput {
+    /// Construct a new empty text input, only the first row of the rect is used
+    pub fn new(rect: TextBufferRect, settings: TextInputSettings) -> Self {
+        Self {
+            rect,
+            text: Vec::new(),
+            cursor: 0,
+            anchor: None,
+            scroll: 0,
+            placeholder: String::new(),
+            validator: None,
+            settings,
+            focused: false,
+            last_rect: None,
+            dirty: true
+        }
+    }
+
+    /// Set the text shown in the placeholder style while the input is empty
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    /// Set a check run on the text after every edit, edits which would make it return false are rejected
+    pub fn validator(mut self, validator: impl Fn(&str) -> bool + 'static) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Returns true if the character can be entered, it must be printable and exist in the code page
+    pub fn accepts_char(c: char) -> bool {
+        !c.is_control() && VGAChar::from_char(c).is_some()
+    }
+
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    /// Replace the text, characters which can not be entered are dropped and the cursor moves to the end. Unlike typed edits this skips the validator
+    pub fn set_text(&mut self, text: &str) {
+        let mut text: Vec<char> = text.chars().filter(|c| Self::accepts_char(*c)).collect();
+        if let Some(max) = self.settings.max_length {
+            text.truncate(max);
+        }
+
+        self.text = text;
+        self.anchor = None;
+        self.set_cursor(self.text.len(), false);
+        self.dirty = true;
+    }
+
+    pub fn placeholder_text(&self) -> &str {
+        &self.placeholder
+    }
+
+    /// Get the cursor position as a character index
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Move the cursor, keeping the selection anchored where it was if extending
+    pub fn set_cursor(&mut self, cursor: usize, extend_selection: bool) {
+        if extend_selection {
+            self.anchor.get_or_insert(self.cursor);
+        }
+        else {
+            self.anchor = None;
+        }
+
+        self.cursor = cursor.min(self.text.len());
+        if self.anchor == Some(self.cursor) {
+            self.anchor = None;
+        }
+
+        self.scroll_to_cursor();
+        self.dirty = true;
+    }
+
+    /// Get the selected range of character indices, if anything is selected
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        self.anchor.map(|anchor| (anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    pub fn selected_text(&self) -> String {
+        self.selection().map(|(start, end)| self.text[start..end].iter().collect()).unwrap_or_default()
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = None;
+        self.cursor = 0;
+        self.set_cursor(self.text.len(), true);
+    }
+
+    /// Get the index of the first character shown
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// Insert text at the cursor, replacing the selection. Returns false and leaves the text unchanged if any character can not be entered, the max length would be passed or the validator rejects the result
+    pub fn insert_str(&mut self, text: &str) -> bool {
+        if !text.chars().all(Self::accepts_char) {
+            return false;
+        }
+
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        let mut edited = self.text.clone();
+        edited.splice(start..end, text.chars());
+        let cursor = start + text.chars().count();
+
+        self.apply_edit(edited, cursor)
+    }
+
+    /// Delete the selection, or the character before the cursor if nothing is selected
+    pub fn backspace(&mut self) -> bool {
+        match self.selection() {
+            Some(_) => self.delete_selection(),
+            None if self.cursor > 0 => self.delete_range(self.cursor - 1, self.cursor),
+            None => false
+        }
+    }
+
+    /// Delete the selection, or the character after the cursor if nothing is selected
+    pub fn delete(&mut self) -> bool {
+        match self.selection() {
+            Some(_) => self.delete_selection(),
+            None if self.cursor < self.text.len() => self.delete_range(self.cursor, self.cursor + 1),
+            None => false
+        }
+    }
+
+    pub fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some((start, end)) => self.delete_range(start, end),
+            None => false
+        }
+    }
+
+    fn delete_range(&mut self, start: usize, end: usize) -> bool {
+        let mut edited = self.text.clone();
+        edited.drain(start..end);
+        self.apply_edit(edited, start)
+    }
+
+    fn apply_edit(&mut self, edited: Vec<char>, cursor: usize) -> bool {
+        if self.settings.max_length.is_some_and(|max| edited.len() > max && edited.len() > self.text.len()) {
+            return false;
+        }
+
+        if let Some(validator) = &self.validator {
+            if !validator(&edited.iter().collect::<String>()) {
+                return false;
+            }
+        }
+
+        self.text = edited;
+        self.set_cursor(cursor, false);
+        true
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        // One cell past the end is kept for the cursor when it sits after the last character
+        let width = self.rect.width.max(1);
+
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        }
+        else if self.cursor >= self.scroll + width {
+            self.scroll = self.cursor + 1 - width;
+        }
+
+        let last = (self.text.len() + 1).saturating_sub(width);
+        self.scroll = self.scroll.min(last);
+    }
+
+    pub fn rect(&self) -> TextBufferRect {
+        self.rect
+    }
+
+    pub fn settings(&self) -> &TextInputSettings {
+        &self.settings
+    }
+
+    pub fn mut_settings(&mut self) -> &mut TextInputSettings {
+        self.dirty = true;
+        &mut self.settings
+    }
+
+    pub fn draw(&mut self, screen: &mut (impl TextBufferInterface + ?Sized)) {
+        let style = if self.focused { self.settings.focused_style } else { self.settings.style };
+        let selection = self.selection();
+
+        let mut cells: Vec<Cell> = if self.text.is_empty() && !self.placeholder.is_empty() {
+            let placeholder = self.placeholder.chars().map(|c| (c, self.settings.placeholder_style));
+            placeholder.chain(std::iter::repeat((' ', style))).take(self.rect.width)
+                .map(|(c, s)| Cell::new(VGAChar::from_char(c).unwrap_or(VGAChar(b'?')), s))
+                .collect()
+        }
+        else {
+            (self.scroll..self.scroll + self.rect.width).map(|i| {
+                let c = self.text.get(i).copied().unwrap_or(' ');
+                let s = match selection {
+                    Some((start, end)) if i >= start && i < end => self.settings.selection_style,
+                    _ => style
+                };
+                Cell::new(VGAChar::from_char(c).unwrap_or(VGAChar(b'?')), s)
+            }).collect()
+        };
+
+        if self.focused {
+            if let Some(cell) = cells.get_mut(self.cursor - self.scroll) {
+                cell.style.attributes |= CellAttributes::REVERSE;
+            }
+        }
+
+        screen.write_data(TextBufferPos { x: self.rect.x, y: self.rect.y }, &cells);
+
+        self.last_rect = Some(TextBufferRect::new(self.rect.x, self.rect.y, self.rect.width, 1));
+    }
+
+    pub fn clear_last(&self, screen: &mut (impl TextBufferInterface + ?Sized)) {
+        if let Some(rect) = self.last_rect {
+            screen.clear_rect(rect);
+        }
+    }
+
+    pub fn take_dirty(&mut self) -> bool {
+        let dirty = self.dirty;
+        self.dirty = false;
+        dirty
+    }
+}
+
+impl UIElement for TextInput {
+    fn take_dirty(&mut self) -> bool {
+        self.take_dirty()
+    }
+
+    fn ui_draw(&mut self, screen: &mut dyn TextBufferInterface) {
+        self.draw(screen)
+    }
+
+    fn clear_last(&self, screen: &mut dyn TextBufferInterface) {
+        // The input draws over its whole row, so only an input which has moved needs clearing
+        if self.last_rect.map(|r| (r.x, r.y, r.width)) != Some((self.rect.x, self.rect.y, self.rect.width)) {
+            self.clear_last(screen)
+        }
+    }
+
+    fn on_event(&mut self, event: &InputEvent) -> bool {
+        let (key, modifiers) = match event {
+            InputEvent::Text(text) => {
+                let text: String = text.chars().filter(|c| Self::accepts_char(*c)).collect();
+                return !text.is_empty() && self.insert_str(&text);
+            }
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Pressed(MouseButton::Left), pos }) if self.hit_test(*pos) => {
+                self.set_cursor(self.scroll + (pos.x - self.rect.x) as usize, false);
+                return true;
+            }
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Dragged(MouseButton::Left), pos }) if self.focused => {
+                // Dragging past either end moves one character at a time so the text scrolls
+                let column = (pos.x - self.rect.x).clamp(-1, self.rect.width as isize);
+                self.set_cursor((self.scroll as isize + column).max(0) as usize, true);
+                return true;
+            }
+            InputEvent::KeyDown { key, modifiers, .. } => (*key, *modifiers),
+            _ => return false
+        };
+
+        let extend = modifiers.shift;
+
+        match key {
+            Key::Left => self.set_cursor(self.cursor.saturating_sub(1), extend),
+            Key::Right => self.set_cursor(self.cursor + 1, extend),
+            Key::Home => self.set_cursor(0, extend),
+            Key::End => self.set_cursor(self.text.len(), extend),
+            Key::Backspace => { self.backspace(); }
+            Key::Delete => { self.delete(); }
+            Key::Char('a') if modifiers == KeyModifiers::CTRL => self.select_all(),
+            // Characters arrive as text events, the key presses which produced them are only swallowed
+            Key::Char(_) if !modifiers.ctrl && !modifiers.alt => (),
+            _ => return false
+        }
+
+        true
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.dirty = true;
+    }
+
+    fn set_rect(&mut self, rect: TextBufferRect) {
+        self.rect = rect;
+        self.scroll_to_cursor();
+        self.dirty = true;
+    }
+
+    fn hit_rect(&self) -> Option<TextBufferRect> {
+        self.last_rect
+    }
+}
@@ -1,50 +1,162 @@
-use crate::screen::{VGAChar, TextBufferRect, CharacterColor};
+use crate::input::{InputEvent, Key, MouseEvent, MouseEventKind};
+use crate::screen::{VGAChar, TextBufferRect, CharacterColor, Color, Cell, CellAttributes, CellStyle, Markup};
 
 use super::UIElement;
 
+/// Number of lines kept by a new text box
+pub const DEFAULT_SCROLLBACK: usize = 500;
+
+/// Rows scrolled for each step of the mouse wheel
+pub const WHEEL_ROWS: usize = 3;
+
+/// Shown in the bottom right corner while the box is scrolled up from the newest text
+pub const MORE_INDICATOR: &str = "more ▼";
+
+/// A bottom anchored log of text, lines are kept whole and wrapped at word boundaries whenever they are drawn so they reflow when the box is resized
 pub struct TextBox {
     rect: TextBufferRect,
-    internal_buffer: Vec<(VGAChar, CharacterColor)>,
+    lines: Vec<Vec<Cell>>,
     dirty: bool,
-    cursor_x: usize,
-    draw_color: CharacterColor,
+    hanging_indent: usize,
+    scrollback: usize,
+    /// First row shown while scrolled up, None while following the newest text
+    scroll_top: Option<usize>,
+    draw_style: CellStyle,
+}
+
+/// Split a line into the ranges shown on each row. Rows break before the last word which does not fit, or mid-word if a word is wider than the row, and the spaces at a break are dropped. Rows after the first are narrowed by the indent
+fn wrap_line(line: &[Cell], width: usize, indent: usize) -> Vec<(usize, usize)> {
+    let is_space = |i: usize| line.get(i).is_some_and(|c| c.character == VGAChar(b' '));
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut row_width = width.max(1);
+
+    while line.len() - start > row_width {
+        let end = (start + 1..=start + row_width).rev()
+            .find(|i| is_space(*i))
+            .unwrap_or(start + row_width);
+        rows.push((start, end));
+
+        start = end;
+        while is_space(start) {
+            start += 1;
+        }
+        row_width = width.saturating_sub(indent).max(1);
+    }
+
+    rows.push((start, line.len()));
+    rows
 }
 
 impl TextBox {
     pub fn new(rect: TextBufferRect) -> Self {
         Self {
             rect,
-            internal_buffer: vec![(VGAChar(b' '), CharacterColor::Gray); rect.area()],
+            lines: vec![Vec::new()],
             dirty: true,
-            cursor_x: 0,
-            draw_color: CharacterColor::White
+            hanging_indent: 0,
+            scrollback: DEFAULT_SCROLLBACK,
+            scroll_top: None,
+            draw_style: CellStyle::new(CharacterColor::White, CharacterColor::Black)
         }
     }
 
-    fn line(&self, i: usize) -> &[(VGAChar, CharacterColor)] {
-        &self.internal_buffer[i * self.rect.width..(i + 1) * self.rect.width]
-    }
+    /// Get the rows shown for each line along with the columns they start at, oldest first
+    fn rows(&self) -> Vec<(usize, &[Cell])> {
+        let mut rows = Vec::new();
 
-    fn scroll(&mut self) {
-        for x in 0..self.rect.width {
-            for y in 0..self.rect.height - 1 {
-                self.internal_buffer[y * self.rect.width + x] = self.internal_buffer[(y + 1) * self.rect.width + x];
+        for line in self.lines.iter() {
+            for (i, (start, end)) in wrap_line(line, self.rect.width, self.hanging_indent).into_iter().enumerate() {
+                let indent = if i == 0 { 0 } else { self.hanging_indent.min(self.rect.width.saturating_sub(1)) };
+                rows.push((indent, &line[start..end]));
             }
-            self.internal_buffer[(self.rect.height - 1) * self.rect.width + x] = (VGAChar(b' '), CharacterColor::Gray);
         }
+
+        rows
     }
 
-    pub fn place_char(&mut self, c: char) {
-        if c == '\n' || self.cursor_x >= self.rect.width {
-            self.cursor_x = 0;
-            self.scroll();
+    fn row_count(&self, line: &[Cell]) -> usize {
+        wrap_line(line, self.rect.width, self.hanging_indent).len()
+    }
+
+    /// Drop the oldest lines past the scrollback limit, keeping a scrolled view on the same rows
+    fn trim(&mut self) {
+        let excess = self.lines.len().saturating_sub(self.scrollback.max(1));
+        if excess == 0 {
+            return;
         }
 
-        if c != '\n' {
-            let vga: VGAChar = c.try_into().unwrap();
+        let removed: usize = self.lines[..excess].iter().map(|line| self.row_count(line)).sum();
+        self.lines.drain(..excess);
+        self.scroll_top = self.scroll_top.map(|top| top.saturating_sub(removed));
+    }
+
+    /// Get the first row which can be shown, where the newest row is at the bottom of the box
+    fn max_top(&self) -> usize {
+        let rows: usize = self.lines.iter().map(|line| self.row_count(line)).sum();
+        rows.saturating_sub(self.rect.height)
+    }
+
+    /// Get the number of rows the view is scrolled up from the newest text
+    pub fn scroll_offset(&self) -> usize {
+        let max_top = self.max_top();
+        self.scroll_top.map_or(0, |top| max_top - top.min(max_top))
+    }
 
-            self.internal_buffer[(self.rect.height - 1) * self.rect.width + self.cursor_x] = (vga, self.draw_color);
-            self.cursor_x += 1;
+    /// Returns true if the newest text is in view, new text only keeps the view at the bottom if it was already there
+    pub fn is_at_bottom(&self) -> bool {
+        self.scroll_offset() == 0
+    }
+
+    /// Scroll towards older text by a number of rows, does nothing while all of the text fits so new text is still followed
+    pub fn scroll_up(&mut self, rows: usize) {
+        let max_top = self.max_top();
+        if max_top == 0 {
+            return;
+        }
+
+        let top = self.scroll_top.unwrap_or(max_top).min(max_top);
+        self.scroll_top = Some(top.saturating_sub(rows));
+        self.dirty = true;
+    }
+
+    /// Scroll towards newer text by a number of rows, following new text again once the bottom is reached
+    pub fn scroll_down(&mut self, rows: usize) {
+        if let Some(top) = self.scroll_top {
+            let top = top + rows;
+            self.scroll_top = if top >= self.max_top() { None } else { Some(top) };
+            self.dirty = true;
+        }
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_top = None;
+        self.dirty = true;
+    }
+
+    pub fn scrollback(&self) -> usize {
+        self.scrollback
+    }
+
+    /// Set how many lines are kept, older lines are dropped once there are more
+    pub fn set_scrollback(&mut self, lines: usize) {
+        self.scrollback = lines;
+        self.trim();
+        self.dirty = true;
+    }
+
+    fn new_line(&mut self) {
+        self.lines.push(Vec::new());
+        self.trim();
+    }
+
+    pub fn place_char(&mut self, c: char) {
+        if c == '\n' {
+            self.new_line();
+        }
+        else {
+            let vga: VGAChar = c.try_into().unwrap();
+            self.lines.last_mut().unwrap().push(Cell::new(vga, self.draw_style));
         }
         self.dirty = true;
     }
@@ -55,32 +167,60 @@ impl TextBox {
         }
     }
 
+    /// Place text with inline style tags, untagged text uses the current style
+    pub fn place_markup(&mut self, markup: &Markup) {
+        for (i, line) in markup.lines().iter().enumerate() {
+            if i > 0 {
+                self.new_line();
+            }
+
+            let cells = line.iter().flat_map(|span| span.text.iter().map(|c| Cell::new(*c, span.style.apply(self.draw_style))));
+            self.lines.last_mut().unwrap().extend(cells);
+        }
+        self.dirty = true;
+    }
+
+    /// Parse and place text with inline style tags
+    pub fn place_markup_str(&mut self, markup: &str) -> Result<(), String> {
+        self.place_markup(&Markup::parse(markup)?);
+        Ok(())
+    }
+
     pub fn clear_color(&mut self) {
-        self.draw_color = CharacterColor::White;
+        self.draw_style = CellStyle::new(CharacterColor::White, CharacterColor::Black);
     }
 
-    pub fn set_color(&mut self, c: CharacterColor) {
-        self.draw_color = c;
+    pub fn set_color(&mut self, c: impl Into<Color>) {
+        self.draw_style.foreground = c.into();
     }
 
-    pub fn update_rect(&mut self, rect: TextBufferRect) {
-        let mut new_internal_buffer = vec![(VGAChar(b' '), CharacterColor::Gray); rect.area()];
-
-        let line_width = rect.width;
-        let last_line = rect.height * line_width;
-
-        for y in 0..self.rect.height {
-            if last_line >= (self.rect.height - y) * line_width {
-                for (i, c) in self.line(y).iter().enumerate() {
-                    if i < rect.width {
-                        new_internal_buffer[last_line - (self.rect.height - y) * line_width + i] = *c;
-                    }
-                }
-            }
-        }
+    pub fn set_background(&mut self, c: impl Into<Color>) {
+        self.draw_style.background = c.into();
+    }
+
+    pub fn set_style(&mut self, style: CellStyle) {
+        self.draw_style = style;
+    }
+
+    pub fn hanging_indent(&self) -> usize {
+        self.hanging_indent
+    }
+
+    /// Set how far rows after the first of a wrapped line are indented
+    pub fn set_hanging_indent(&mut self, indent: usize) {
+        self.hanging_indent = indent;
+        self.dirty = true;
+    }
+
+    /// Get the text of each line, without wrapping
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().map(|line| line.iter().map(|c| c.character.to_char()).collect()).collect()
+    }
 
+    /// Move and resize the box, the lines are reflowed to the new width
+    pub fn update_rect(&mut self, rect: TextBufferRect) {
         self.rect = rect;
-        self.internal_buffer = new_internal_buffer;
+        self.dirty = true;
     }
 }
 
@@ -91,15 +231,60 @@ impl UIElement for TextBox {
         dirty
     }
 
-    fn ui_draw(&mut self, screen: &mut impl crate::screen::TextBufferInterface) {
-        for y in 0..self.rect.height {
-            screen.write_data((self.rect.x, self.rect.y + y as isize).into(), self.line(y));
+    fn ui_draw(&mut self, screen: &mut dyn crate::screen::TextBufferInterface) {
+        screen.clear_rect(self.rect);
+
+        let offset = self.scroll_offset();
+        let rows = self.rows();
+        let shown = rows.len().min(self.rect.height);
+        let first = rows.len() - shown - offset;
+        let top = self.rect.bottom() - shown as isize;
+
+        for (y, (indent, cells)) in rows[first..first + shown].iter().enumerate() {
+            screen.write_data((self.rect.x + *indent as isize, top + y as isize).into(), cells);
+        }
+
+        if offset > 0 && self.rect.height > 0 {
+            let style = CellStyle::new(CharacterColor::White, CharacterColor::Black).with(CellAttributes::REVERSE);
+            let indicator: Vec<Cell> = MORE_INDICATOR.chars().filter_map(VGAChar::from_char).map(|c| Cell::new(c, style)).collect();
+            let shown = &indicator[..indicator.len().min(self.rect.width)];
+
+            screen.write_data((self.rect.right() - shown.len() as isize, self.rect.bottom() - 1).into(), shown);
         }
     }
 
-    fn clear_last(&self, screen: &mut impl crate::screen::TextBufferInterface) {
+    fn clear_last(&self, screen: &mut dyn crate::screen::TextBufferInterface) {
         screen.clear_rect(self.rect)
     }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, event: &InputEvent) -> bool {
+        let page = self.rect.height.saturating_sub(1).max(1);
+
+        match event {
+            InputEvent::KeyDown { key: Key::PageUp, .. } => self.scroll_up(page),
+            InputEvent::KeyDown { key: Key::PageDown, .. } => self.scroll_down(page),
+            InputEvent::KeyDown { key: Key::End, .. } => self.scroll_to_bottom(),
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Wheel { y, .. }, pos }) if self.hit_test(*pos) => match *y {
+                y if y > 0 => self.scroll_up(y as usize * WHEEL_ROWS),
+                y => self.scroll_down(y.unsigned_abs() as usize * WHEEL_ROWS)
+            },
+            _ => return false
+        }
+
+        true
+    }
+
+    fn set_rect(&mut self, rect: TextBufferRect) {
+        self.update_rect(rect);
+    }
+
+    fn hit_rect(&self) -> Option<TextBufferRect> {
+        Some(self.rect)
+    }
 }
 
 impl std::fmt::Write for TextBox {
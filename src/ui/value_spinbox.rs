@@ -0,0 +1,326 @@
+use std::fmt::Display;
+
+use crate::input::{InputEvent, Key, MouseButton, MouseEvent, MouseEventKind};
+use crate::screen::{Cell, CellStyle, CharacterColor, Drawable, TextAlign, TextBufferInterface, TextBufferPos, TextBufferRect, TextFormatting, VGAChar};
+
+use super::UIElement;
+
+/// Numeric types which can be shown and stepped by a spinbox
+pub trait SpinValue: Copy + PartialOrd + Display {
+    /// Add a step, returning None if the result can not be represented
+    fn checked_step_up(self, step: Self) -> Option<Self>;
+
+    /// Subtract a step, returning None if the result can not be represented
+    fn checked_step_down(self, step: Self) -> Option<Self>;
+
+    /// Format the value with a fixed number of decimal places, integers are treated as fixed point numbers scaled by 10 to the power of the decimal places
+    fn format_fixed(&self, decimals: usize) -> String;
+}
+
+macro_rules! impl_spin_value_integer {
+    ($($t:ty),*) => {
+        $(
+            impl SpinValue for $t {
+                fn checked_step_up(self, step: Self) -> Option<Self> {
+                    self.checked_add(step)
+                }
+
+                fn checked_step_down(self, step: Self) -> Option<Self> {
+                    self.checked_sub(step)
+                }
+
+                fn format_fixed(&self, decimals: usize) -> String {
+                    if decimals == 0 {
+                        return self.to_string();
+                    }
+
+                    let scale = 10u128.pow(decimals as u32);
+                    let magnitude = (*self as i128).unsigned_abs();
+                    let sign = if (*self as i128) < 0 { "-" } else { "" };
+
+                    format!("{}{}.{:0width$}", sign, magnitude / scale, magnitude % scale, width = decimals)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_spin_value_float {
+    ($($t:ty),*) => {
+        $(
+            impl SpinValue for $t {
+                fn checked_step_up(self, step: Self) -> Option<Self> {
+                    Some(self + step).filter(|v| v.is_finite())
+                }
+
+                fn checked_step_down(self, step: Self) -> Option<Self> {
+                    Some(self - step).filter(|v| v.is_finite())
+                }
+
+                fn format_fixed(&self, decimals: usize) -> String {
+                    format!("{:.*}", decimals, self)
+                }
+            }
+        )*
+    };
+}
+
+impl_spin_value_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_spin_value_float!(f32, f64);
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpinboxSettings {
+    pub style: CellStyle,
+    pub focused_style: CellStyle,
+    pub text_align: TextAlign,
+    pub wrapping: bool,
+    pub unit: Option<&'static str>,
+    pub decimals: Option<usize>
+}
+
+impl SpinboxSettings {
+    pub const fn new() -> Self {
+        Self {
+            style: CellStyle::new(CharacterColor::White, CharacterColor::Black),
+            focused_style: CellStyle::new(CharacterColor::Yellow, CharacterColor::Black),
+            text_align: TextAlign::Left,
+            wrapping: false,
+            unit: None,
+            decimals: None
+        }
+    }
+
+    pub const fn style(mut self, style: CellStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub const fn focused_style(mut self, style: CellStyle) -> Self {
+        self.focused_style = style;
+        self
+    }
+
+    pub const fn align(mut self, align: TextAlign) -> Self {
+        self.text_align = align;
+        self
+    }
+
+    /// Step past the maximum to the minimum and back instead of stopping at the ends
+    pub const fn wrapping(mut self) -> Self {
+        self.wrapping = true;
+        self
+    }
+
+    /// Show a unit after the value
+    pub const fn unit(mut self, unit: &'static str) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Show the value with a fixed number of decimal places, integer values are treated as fixed point
+    pub const fn fixed_point(mut self, decimals: usize) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+}
+
+impl std::default::Default for SpinboxSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A numeric value between a minimum and maximum which is changed in steps, drawn as `◄ value ►`
+pub struct ValueSpinbox<T> {
+    value: T,
+    min: T,
+    max: T,
+    step: T,
+    pos: TextBufferPos,
+    settings: SpinboxSettings,
+    focused: bool,
+    last_rect: Option<TextBufferRect>,
+    dirty: bool
+}
+
+impl<T: SpinValue> ValueSpinbox<T> {
+    /// Construct a new spinbox, the value is clamped to the range
+    pub fn new(value: T, min: T, max: T, step: T, pos: TextBufferPos, settings: SpinboxSettings) -> Self {
+        let mut result = Self {
+            value: min,
+            min,
+            max,
+            step,
+            pos,
+            settings,
+            focused: false,
+            last_rect: None,
+            dirty: true
+        };
+
+        result.set_value(value);
+        result
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Set the value, clamping it to the range
+    pub fn set_value(&mut self, value: T) {
+        let value = if value < self.min { self.min } else if value > self.max { self.max } else { value };
+
+        if value != self.value {
+            self.value = value;
+            self.dirty = true;
+        }
+    }
+
+    pub fn min(&self) -> T {
+        self.min
+    }
+
+    pub fn max(&self) -> T {
+        self.max
+    }
+
+    pub fn step(&self) -> T {
+        self.step
+    }
+
+    /// Step the value up, stopping at or wrapping past the maximum
+    pub fn increment(&mut self) {
+        match self.value.checked_step_up(self.step) {
+            Some(v) if v <= self.max => self.set_value(v),
+            _ if self.settings.wrapping => self.set_value(self.min),
+            _ => self.set_value(self.max)
+        }
+    }
+
+    /// Step the value down, stopping at or wrapping past the minimum
+    pub fn decrement(&mut self) {
+        match self.value.checked_step_down(self.step) {
+            Some(v) if v >= self.min => self.set_value(v),
+            _ if self.settings.wrapping => self.set_value(self.max),
+            _ => self.set_value(self.min)
+        }
+    }
+
+    /// Get the value as it is shown, including the unit
+    pub fn value_text(&self) -> String {
+        let mut text = match self.settings.decimals {
+            Some(decimals) => self.value.format_fixed(decimals),
+            None => self.value.to_string()
+        };
+
+        if let Some(unit) = self.settings.unit {
+            text += " ";
+            text += unit;
+        }
+
+        text
+    }
+
+    pub fn draw(&mut self, screen: &mut (impl TextBufferInterface + ?Sized)) {
+        let style = if self.focused { self.settings.focused_style } else { self.settings.style };
+        self.last_rect = Some(Drawable::draw(self, screen, self.pos, &(style, self.settings.text_align).into()));
+    }
+
+    pub fn clear_last(&self, screen: &mut (impl TextBufferInterface + ?Sized)) {
+        if let Some(rect) = self.last_rect {
+            screen.clear_rect(rect);
+        }
+    }
+
+    pub fn take_dirty(&mut self) -> bool {
+        let dirty = self.dirty;
+        self.dirty = false;
+        dirty
+    }
+
+    pub fn settings(&self) -> &SpinboxSettings {
+        &self.settings
+    }
+
+    pub fn mut_settings(&mut self) -> &mut SpinboxSettings {
+        self.dirty = true;
+        &mut self.settings
+    }
+
+    pub fn set_pos(&mut self, pos: TextBufferPos) {
+        self.pos = pos;
+        self.dirty = true;
+    }
+}
+
+impl<T: SpinValue> UIElement for ValueSpinbox<T> {
+    fn take_dirty(&mut self) -> bool {
+        self.take_dirty()
+    }
+
+    fn ui_draw(&mut self, screen: &mut dyn TextBufferInterface) {
+        self.draw(screen)
+    }
+
+    fn clear_last(&self, screen: &mut dyn TextBufferInterface) {
+        self.clear_last(screen)
+    }
+
+    fn on_event(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::KeyDown { key: Key::Left | Key::Down, .. } => self.decrement(),
+            InputEvent::KeyDown { key: Key::Right | Key::Up, .. } => self.increment(),
+            InputEvent::KeyDown { key: Key::Home, .. } => self.set_value(self.min),
+            InputEvent::KeyDown { key: Key::End, .. } => self.set_value(self.max),
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Wheel { y, .. }, pos }) if self.hit_test(*pos) => match *y {
+                y if y > 0 => self.increment(),
+                y if y < 0 => self.decrement(),
+                _ => ()
+            },
+            // The arrows at either end step the value
+            InputEvent::Mouse(MouseEvent { kind: MouseEventKind::Pressed(MouseButton::Left), pos }) if self.hit_test(*pos) => {
+                let rect = self.last_rect.unwrap();
+                if pos.x == rect.x {
+                    self.decrement();
+                }
+                else if pos.x == rect.right() - 1 {
+                    self.increment();
+                }
+            }
+            _ => return false
+        }
+
+        true
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.dirty = true;
+    }
+
+    fn hit_rect(&self) -> Option<TextBufferRect> {
+        self.last_rect
+    }
+}
+
+impl<T: SpinValue> Drawable<TextFormatting> for ValueSpinbox<T> {
+    fn draw(&self, screen: &mut (impl TextBufferInterface + ?Sized), pos: TextBufferPos, settings: &TextFormatting) -> TextBufferRect {
+        let mut total = Vec::new();
+
+        total.push(Cell::new(VGAChar(0x11), settings.style));
+        total.push(Cell::new(VGAChar(b' '), settings.style));
+
+        for c in self.value_text().chars() {
+            total.push(Cell::new(VGAChar::from_char(c).unwrap_or(VGAChar(b'?')), settings.style));
+        }
+
+        total.push(Cell::new(VGAChar(b' '), settings.style));
+        total.push(Cell::new(VGAChar(0x10), settings.style));
+
+        screen.write_data_align(pos, &total, settings.alignment)
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use asciiengine::app::{App, FixedTimestep, GameLoop, LoopSettings};
+use asciiengine::input::{InputEvent, Key, KeyModifiers};
+use asciiengine::screen::{set_blink_interval, TextBufferScreen};
+
+#[derive(Default)]
+struct Counter {
+    ticks: usize,
+    events: usize,
+    renders: usize,
+    changed: bool
+}
+
+impl App for Counter {
+    fn handle_event(&mut self, _event: &InputEvent) {
+        self.events += 1;
+        self.changed = true;
+    }
+
+    fn update(&mut self, dt: Duration) {
+        assert_eq!(dt, Duration::from_millis(10));
+        self.ticks += 1;
+    }
+
+    fn render(&mut self, screen: &mut TextBufferScreen) {
+        self.renders += 1;
+        if self.changed {
+            screen.write_string((0, 0).into(), &self.events.to_string(), Default::default()).unwrap();
+            self.changed = false;
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.ticks < 100
+    }
+}
+
+#[test]
+fn timestep_carries_remainder_and_drops_stalls() {
+    let mut timestep = FixedTimestep::new(100, 4);
+    assert_eq!(timestep.step(), Duration::from_millis(10));
+
+    assert_eq!(timestep.advance(Duration::from_millis(25)), 2);
+    assert_eq!(timestep.until_next(), Duration::from_millis(5));
+    assert!((timestep.alpha() - 0.5).abs() < 1e-4);
+    assert_eq!(timestep.advance(Duration::from_millis(5)), 1);
+
+    // A long stall runs the most ticks allowed and forgets the rest
+    assert_eq!(timestep.advance(Duration::from_secs(2)), 4);
+    assert_eq!(timestep.until_next(), Duration::from_millis(10));
+}
+
+#[test]
+fn loop_skips_idle_frames_and_limits_fps() {
+    // Keep the blink clock from marking the screen dirty partway through
+    set_blink_interval(u64::MAX / 2);
+
+    let mut app = Counter::default();
+    let mut screen = TextBufferScreen::new(4, 1);
+    let mut game_loop = GameLoop::new(LoopSettings::new().tick_rate(100).fps(50));
+    let start = Instant::now();
+
+    // The first frame renders the app but runs no ticks
+    let frame = game_loop.frame(&mut app, &mut screen, &[], start);
+    assert_eq!((frame.ticks, frame.present, app.renders), (0, true, 1));
+    assert_eq!(frame.next_frame, start + Duration::from_millis(20));
+    screen.take_dirty();
+    screen.get_and_clear_optimization_flag();
+
+    // Ticks ran but nothing changed, so the loop waits for the next tick instead of presenting
+    let now = start + Duration::from_millis(23);
+    let frame = game_loop.frame(&mut app, &mut screen, &[], now);
+    assert_eq!((frame.ticks, frame.present, app.renders), (2, false, 2));
+    assert_eq!(frame.next_frame, now + Duration::from_millis(7));
+
+    // Nothing due yet and no input, so the app is not even asked to render
+    let frame = game_loop.frame(&mut app, &mut screen, &[], now + Duration::from_millis(1));
+    assert_eq!((frame.ticks, frame.present, app.renders), (0, false, 2));
+
+    let now = now + Duration::from_millis(2);
+    let frame = game_loop.frame(&mut app, &mut screen, &[InputEvent::key(Key::Enter, KeyModifiers::NONE)], now);
+    assert_eq!((frame.ticks, frame.present, app.events, app.renders), (0, true, 1, 3));
+    assert_eq!(frame.next_frame, now + Duration::from_millis(20));
+    assert!(game_loop.is_running());
+
+    game_loop.frame(&mut app, &mut screen, &[InputEvent::Quit], now);
+    assert!(!game_loop.is_running());
+}
+
+#[test]
+fn loop_stops_when_app_finishes() {
+    let mut app = Counter::default();
+    let mut screen = TextBufferScreen::new(4, 1);
+    let mut game_loop = GameLoop::new(LoopSettings::new().tick_rate(100).max_ticks_per_frame(1000).uncapped());
+    let start = Instant::now();
+
+    game_loop.frame(&mut app, &mut screen, &[], start);
+    let frame = game_loop.frame(&mut app, &mut screen, &[], start + Duration::from_secs(1));
+    assert_eq!(frame.ticks, 100);
+    assert!(!game_loop.is_running());
+}
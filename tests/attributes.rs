@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+use asciiengine::screen::{blink_visible, set_blink_interval, CellAttributes, CellStyle, CharacterColor, Color, Palette, TextBufferRect, TextBufferScreen};
+
+#[test]
+fn attribute_sets() {
+    let mut attributes = CellAttributes::REVERSE | CellAttributes::BLINK;
+    assert!(attributes.contains(CellAttributes::REVERSE));
+    assert!(!attributes.contains(CellAttributes::REVERSE | CellAttributes::DIM));
+
+    attributes |= CellAttributes::DIM;
+    assert!(attributes.contains(CellAttributes::REVERSE | CellAttributes::DIM));
+
+    let attributes = attributes.difference(CellAttributes::REVERSE | CellAttributes::BLINK | CellAttributes::DIM);
+    assert!(attributes.is_empty());
+    assert_eq!(attributes, CellAttributes::NONE);
+}
+
+#[test]
+fn reverse_and_dim_change_display_colors() {
+    let style = CellStyle::from_colors(Color::Rgb(200, 100, 50), Color::Named(CharacterColor::Blue));
+    let blue = Palette::VGA.get(CharacterColor::Blue);
+
+    assert_eq!(style.display_colors(&Palette::VGA), ((200, 100, 50), blue));
+    assert_eq!(style.with(CellAttributes::REVERSE).display_colors(&Palette::VGA), (blue, (200, 100, 50)));
+    assert_eq!(style.with(CellAttributes::DIM).display_colors(&Palette::VGA), ((100, 50, 25), blue));
+
+    // Dimming applies to whichever color ends up in front
+    let reversed_dim = style.attributes(CellAttributes::REVERSE | CellAttributes::DIM).display_colors(&Palette::VGA);
+    assert_eq!(reversed_dim, ((blue.0 / 2, blue.1 / 2, blue.2 / 2), (200, 100, 50)));
+
+    // Underline and strikethrough leave the colors alone
+    assert_eq!(style.with(CellAttributes::UNDERLINE | CellAttributes::STRIKETHROUGH).display_colors(&Palette::VGA), style.display_colors(&Palette::VGA));
+}
+
+#[test]
+fn blinking_glyphs_follow_the_blink_clock() {
+    let plain = CellStyle::default();
+    let blinking = plain.with(CellAttributes::BLINK);
+
+    assert!(plain.glyph_visible(false));
+    assert!(blinking.glyph_visible(true));
+    assert!(!blinking.glyph_visible(false));
+
+    set_blink_interval(10);
+    let (mut shown, mut hidden) = (false, false);
+    let start = Instant::now();
+    while !(shown && hidden) && start.elapsed() < Duration::from_secs(2) {
+        if blink_visible() { shown = true } else { hidden = true }
+        std::thread::sleep(Duration::from_millis(2));
+    }
+    set_blink_interval(500);
+
+    assert!(shown && hidden);
+}
+
+#[test]
+fn blinking_cells_are_redrawn_when_the_clock_changes() {
+    let mut screen = TextBufferScreen::new(8, 3);
+    screen.write_string((1, 0).into(), "a", CellStyle::default().with(CellAttributes::BLINK)).unwrap();
+    screen.write_string((5, 0).into(), "b", CellStyle::default().with(CellAttributes::BLINK)).unwrap();
+    screen.write_string((2, 2).into(), "c", CellStyle::default().with(CellAttributes::UNDERLINE)).unwrap();
+    screen.take_dirty();
+
+    screen.mark_blinking_dirty();
+    assert_eq!(screen.take_dirty(), vec![TextBufferRect::new(1, 0, 5, 1)]);
+}
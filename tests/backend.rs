@@ -0,0 +1,91 @@
+use asciiengine::backend::{RenderBackend, RenderContext};
+use asciiengine::screen::{Cell, CharacterColor, Palette, TextBufferPos, TextBufferScreen, VGAChar};
+
+/// Backend which records what it was asked to draw
+#[derive(Default)]
+struct Recorder {
+    frames: Vec<((usize, usize), bool)>,
+    cells: Vec<(isize, isize, u8)>,
+    palette: Option<Palette>,
+    ended: usize,
+    fail: bool
+}
+
+impl RenderBackend for Recorder {
+    fn begin_frame(&mut self, size: (usize, usize), clear: bool, context: &RenderContext) -> Result<(), String> {
+        self.frames.push((size, clear));
+        self.cells.clear();
+        self.palette = Some(context.palette.clone());
+        Ok(())
+    }
+
+    fn draw_cell(&mut self, pos: TextBufferPos, cell: &Cell, _context: &RenderContext) -> Result<(), String> {
+        if self.fail {
+            return Err("backend failed".to_string());
+        }
+        self.cells.push((pos.x, pos.y, cell.character.0));
+        Ok(())
+    }
+
+    fn end_frame(&mut self) -> Result<(), String> {
+        self.ended += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn renders_only_dirty_cells() {
+    let mut screen = TextBufferScreen::new(4, 2);
+    let mut backend = Recorder::default();
+
+    // The first frame clears the display, so blank cells are skipped
+    screen.write_string((1, 0).into(), "ab", CharacterColor::White.into()).unwrap();
+    screen.render(&mut backend).unwrap();
+    assert_eq!(backend.frames, vec![((4, 2), true)]);
+    assert_eq!(backend.cells, vec![(1, 0, b'a'), (2, 0, b'b')]);
+    assert_eq!(backend.ended, 1);
+
+    screen.render(&mut backend).unwrap();
+    assert_eq!(backend.frames[1], ((4, 2), false));
+    assert!(backend.cells.is_empty());
+
+    // Later frames redraw changed cells even when they are blank
+    screen.write_string((2, 1).into(), "c", CharacterColor::White.into()).unwrap();
+    screen.write_string((1, 0).into(), " ", CharacterColor::Gray.into()).unwrap();
+    screen.render(&mut backend).unwrap();
+    assert_eq!(backend.cells, vec![(2, 1, b'c'), (1, 0, b' ')]);
+    assert_eq!(backend.ended, 3);
+}
+
+#[test]
+fn context_carries_the_palette() {
+    let mut screen = TextBufferScreen::new(2, 1);
+    let mut backend = Recorder::default();
+
+    screen.set_palette(Palette::SOLARIZED);
+    screen.render(&mut backend).unwrap();
+    assert_eq!(backend.palette, Some(Palette::SOLARIZED));
+}
+
+#[test]
+fn full_renders_leave_dirty_regions_alone() {
+    let mut screen = TextBufferScreen::new(3, 1);
+    screen.write_data((0, 0).into(), &[Cell::new(VGAChar(b'x'), CharacterColor::Red.into())]);
+    let dirty = screen.dirty_regions().to_vec();
+
+    let mut backend = Recorder::default();
+    screen.render_full(&mut backend).unwrap();
+    assert_eq!(backend.frames, vec![((3, 1), true)]);
+    assert_eq!(backend.cells, vec![(0, 0, b'x')]);
+    assert_eq!(screen.dirty_regions(), dirty.as_slice());
+}
+
+#[test]
+fn backend_errors_are_returned() {
+    let mut screen = TextBufferScreen::new(2, 1);
+    screen.write_string((0, 0).into(), "x", CharacterColor::White.into()).unwrap();
+
+    let mut backend = Recorder { fail: true, ..Recorder::default() };
+    assert_eq!(screen.render(&mut backend), Err("backend failed".to_string()));
+    assert_eq!(backend.ended, 0);
+}
@@ -0,0 +1,97 @@
+use asciiengine::input::{InputEvent, InputMap, Key, KeyChord, KeyModifiers, RepeatPolicy};
+
+fn down(key: Key, modifiers: KeyModifiers, repeat: bool) -> InputEvent {
+    InputEvent::KeyDown { key, modifiers, repeat }
+}
+
+fn up(key: Key, modifiers: KeyModifiers) -> InputEvent {
+    InputEvent::KeyUp { key, modifiers }
+}
+
+#[test]
+fn chords_parse_and_print() {
+    assert_eq!(KeyChord::parse("Ctrl+Shift+S"), Ok(KeyChord::new(Key::Char('s'), KeyModifiers { shift: true, ctrl: true, alt: false })));
+    assert_eq!(KeyChord::parse("space"), Ok(KeyChord::key(Key::Char(' '))));
+    assert_eq!(KeyChord::parse("f11"), Ok(KeyChord::key(Key::Function(11))));
+    assert_eq!(KeyChord::parse("alt + pageup"), Ok(KeyChord::new(Key::PageUp, KeyModifiers::ALT)));
+    assert!(KeyChord::parse("hyper+a").is_err());
+    assert!(KeyChord::parse("ctrl+").is_err());
+    assert!(KeyChord::parse("f99").is_err());
+
+    for text in ["ctrl+alt+shift+enter", "plus", "shift+comma", "f5", "q", "ctrl+space"] {
+        assert_eq!(KeyChord::parse(text).unwrap().to_string(), text);
+    }
+}
+
+#[test]
+fn actions_track_pressed_held_and_released() {
+    let mut map = InputMap::new()
+        .bind("jump", Key::Char(' '))
+        .bind("save", KeyChord::new(Key::Char('s'), KeyModifiers::CTRL))
+        .bind("left", Key::Left)
+        .bind("left", Key::Char('a'))
+        .repeat("left", RepeatPolicy::Repeat);
+
+    assert!(map.handle_event(&down(Key::Char(' '), KeyModifiers::NONE, false)));
+    assert!(map.pressed("jump") && map.held("jump") && !map.released("jump"));
+    map.next_frame();
+
+    // Repeats of an action which ignores them do not press it again
+    map.handle_event(&down(Key::Char(' '), KeyModifiers::NONE, true));
+    assert!(!map.pressed("jump") && map.held("jump"));
+
+    assert!(map.handle_event(&up(Key::Char(' '), KeyModifiers::NONE)));
+    assert!(map.released("jump") && !map.held("jump"));
+    map.next_frame();
+    assert!(!map.released("jump"));
+
+    map.handle_event(&down(Key::Char('a'), KeyModifiers::NONE, false));
+    map.next_frame();
+    map.handle_event(&down(Key::Char('a'), KeyModifiers::NONE, true));
+    assert_eq!(map.pressed_actions().collect::<Vec<_>>(), vec!["left"]);
+
+    // Modifiers must match exactly, but letting go of them first still releases the action
+    assert!(!map.handle_event(&down(Key::Char('s'), KeyModifiers::NONE, false)));
+    assert!(!map.handle_event(&down(Key::Char('s'), KeyModifiers { shift: false, ctrl: true, alt: true }, false)));
+    assert!(map.handle_event(&down(Key::Char('s'), KeyModifiers::CTRL, false)));
+    assert!(map.pressed("save"));
+    map.handle_event(&up(Key::Char('s'), KeyModifiers::NONE));
+    assert!(map.released("save") && !map.held("save"));
+
+    map.release_all();
+    assert!(!map.held("left") && map.released("left"));
+    assert!(!map.pressed("missing") && !map.held("missing"));
+}
+
+#[test]
+fn config_round_trips_and_overrides_defaults() {
+    let mut map = InputMap::new()
+        .bind("jump", Key::Char(' '))
+        .bind("jump", Key::Char('w'))
+        .bind("menu", Key::Escape);
+
+    assert_eq!(map.to_config(), "jump = space, w\nmenu = escape\n");
+    assert_eq!(map.binding_labels(), vec!["jump: space, w", "menu: escape"]);
+
+    map.apply_config("# user changes\n\nmenu = ctrl+m, f10\nfire = shift+enter\n").unwrap();
+    assert_eq!(map.bindings("jump"), &[KeyChord::key(Key::Char(' ')), KeyChord::key(Key::Char('w'))]);
+    assert_eq!(map.bindings("menu"), &[KeyChord::new(Key::Char('m'), KeyModifiers::CTRL), KeyChord::key(Key::Function(10))]);
+    assert_eq!(map.actions().collect::<Vec<_>>(), vec!["jump", "menu", "fire"]);
+
+    let mut reloaded = InputMap::new();
+    reloaded.apply_config(&map.to_config()).unwrap();
+    assert_eq!(reloaded.to_config(), map.to_config());
+
+    assert!(map.apply_config("jump space").unwrap_err().contains("line 1"));
+    assert!(map.apply_config("\njump = banana").unwrap_err().contains("line 2"));
+
+    map.unbind(KeyChord::key(Key::Char('w')));
+    assert_eq!(map.actions_for(KeyChord::key(Key::Char('w'))).count(), 0);
+    assert_eq!(map.actions_for(KeyChord::key(Key::Char(' '))).collect::<Vec<_>>(), vec!["jump"]);
+
+    // Capturing a new binding from a key press
+    let chord = KeyChord::from_event(&down(Key::Char('j'), KeyModifiers::ALT, false)).unwrap();
+    map.set_bindings("jump", &[chord]);
+    assert_eq!(map.bindings("jump"), &[chord]);
+    assert_eq!(KeyChord::from_event(&down(Key::Char('j'), KeyModifiers::ALT, true)), None);
+}
@@ -0,0 +1,72 @@
+use asciiengine::screen::{Cell, CellStyle, CharacterColor, Color, TextAlign, TextBufferInterface, TextBufferScreen, TextView, VGAChar};
+
+fn colors(screen: &TextBufferScreen, x: isize, y: isize) -> (Color, Color) {
+    let style = screen.char_ref((x, y).into()).unwrap().style;
+    (style.foreground, style.background)
+}
+
+#[test]
+fn cells_keep_their_own_colors() {
+    let mut screen = TextBufferScreen::new(6, 2);
+
+    screen.write_string((0, 0).into(), "ab", CellStyle::new(CharacterColor::Red, CharacterColor::Blue)).unwrap();
+    screen.write_string((2, 0).into(), "c", CharacterColor::Yellow.into()).unwrap();
+    screen.write_data((0, 1).into(), &[
+        Cell::new(VGAChar(b'x'), CellStyle::new(CharacterColor::Green, CharacterColor::Magenta)),
+        (VGAChar(b'y'), CharacterColor::Cyan).into()
+    ]);
+
+    assert_eq!(colors(&screen, 1, 0), (Color::Named(CharacterColor::Red), Color::Named(CharacterColor::Blue)));
+    assert_eq!(colors(&screen, 2, 0), (Color::Named(CharacterColor::Yellow), Color::Named(CharacterColor::Black)));
+    assert_eq!(colors(&screen, 0, 1), (Color::Named(CharacterColor::Green), Color::Named(CharacterColor::Magenta)));
+    assert_eq!(colors(&screen, 1, 1), (Color::Named(CharacterColor::Cyan), Color::Named(CharacterColor::Black)));
+    assert_eq!(screen.char_ref((1, 1).into()).unwrap().character, VGAChar(b'y'));
+
+    // Untouched cells are blank
+    assert_eq!(screen.char_ref((5, 1).into()), Some(&Cell::blank()));
+    assert_eq!(screen.char_ref((6, 1).into()), None);
+}
+
+#[test]
+fn aligned_writes_keep_colors() {
+    let mut screen = TextBufferScreen::new(6, 1);
+    let style = CellStyle::new(CharacterColor::White, CharacterColor::Red);
+
+    // Right aligned text ends just before the position
+    let rect = screen.write_string_align((6, 0).into(), "end", style, TextAlign::Right).unwrap();
+    assert_eq!(rect, (3, 0, 3, 1).into());
+    assert_eq!(screen.char_ref((3, 0).into()), Some(&Cell::new(VGAChar(b'e'), style)));
+    assert_eq!(screen.char_ref((2, 0).into()), Some(&Cell::blank()));
+}
+
+#[test]
+fn clearing_with_a_background() {
+    let mut screen = TextBufferScreen::new(4, 3);
+    screen.write_string((0, 0).into(), "abcd", CellStyle::new(CharacterColor::White, CharacterColor::Red)).unwrap();
+
+    screen.clear_rect_background((1, 0, 2, 2).into(), Color::Named(CharacterColor::Blue));
+    let cleared = screen.char_ref((1, 0).into()).unwrap();
+    assert_eq!(cleared.character, VGAChar(b' '));
+    assert_eq!(cleared.style.background, Color::Named(CharacterColor::Blue));
+    assert!(!cleared.is_blank());
+    assert_eq!(colors(&screen, 0, 0).1, Color::Named(CharacterColor::Red));
+
+    screen.clear_rect(screen.screen_rect());
+    assert!((0..4).all(|x| (0..3).all(|y| screen.char_ref((x, y).into()).unwrap().is_blank())));
+}
+
+#[test]
+fn views_write_colored_cells_into_the_parent() {
+    let mut screen = TextBufferScreen::new(6, 3);
+    let style = CellStyle::new(CharacterColor::LightGreen, CharacterColor::Brown);
+
+    let mut view = TextView::new((2, 1, 3, 2).into(), &mut screen);
+    view.write_string((0, 0).into(), "hello", style).unwrap();
+    view.fill_rect((0, 1, 10, 1).into(), Cell::new(VGAChar(b'#'), style));
+
+    assert_eq!(screen.char_ref((2, 1).into()), Some(&Cell::new(VGAChar(b'h'), style)));
+    assert_eq!(screen.char_ref((4, 1).into()), Some(&Cell::new(VGAChar(b'l'), style)));
+    assert_eq!(screen.char_ref((5, 1).into()), Some(&Cell::blank()));
+    assert_eq!(screen.char_ref((4, 2).into()), Some(&Cell::new(VGAChar(b'#'), style)));
+    assert_eq!(screen.char_ref((5, 2).into()), Some(&Cell::blank()));
+}
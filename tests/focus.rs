@@ -0,0 +1,85 @@
+use asciiengine::input::{InputEvent, Key, KeyModifiers};
+use asciiengine::screen::{CharacterColor, Color, TextAlign, TextBufferScreen};
+use asciiengine::snapshot::text_snapshot;
+use asciiengine::ui::{self, FocusManager, MenuSettings};
+
+fn menu(items: Vec<&'static str>) -> ui::GraphicalMenu<&'static str> {
+    ui::GraphicalMenu::new(items, (0, 0).into(), MenuSettings::new().vertical().hide_others())
+}
+
+fn border_color(screen: &TextBufferScreen, x: isize) -> Color {
+    screen.char_ref((x, 0).into()).unwrap().style.foreground
+}
+
+#[test]
+fn tab_cycles_focus_and_highlights_border() {
+    let mut screen = TextBufferScreen::new(24, 5);
+    let mut widgets = FocusManager::new();
+
+    widgets.push_pane(menu(vec!["A", "B"]), (0, 0, 12, 5).into(), Some(("One", TextAlign::Center)));
+    widgets.push(ui::DisplayBox::new((0, 4, 1, 1).into(), "", 0, ui::DisplayBoxSettings::new()));
+    widgets.push_pane(menu(vec!["C", "D"]), (12, 0, 12, 5).into(), Some(("Two", TextAlign::Center)));
+
+    widgets.draw(&mut screen);
+    assert_eq!(widgets.focused(), Some(0));
+    assert_eq!(border_color(&screen, 0), Color::Named(CharacterColor::Yellow));
+    assert_eq!(border_color(&screen, 12), Color::Named(CharacterColor::BrightWhite));
+
+    // The display box can not take focus so it is skipped
+    assert!(widgets.handle_event(&InputEvent::key(Key::Tab, KeyModifiers::NONE)));
+    assert_eq!(widgets.focused(), Some(2));
+
+    widgets.draw(&mut screen);
+    assert_eq!(border_color(&screen, 0), Color::Named(CharacterColor::BrightWhite));
+    assert_eq!(border_color(&screen, 12), Color::Named(CharacterColor::Yellow));
+
+    assert!(widgets.handle_event(&InputEvent::key(Key::Tab, KeyModifiers::SHIFT)));
+    assert_eq!(widgets.focused(), Some(0));
+    assert!(widgets.handle_event(&InputEvent::key(Key::Tab, KeyModifiers::SHIFT)));
+    assert_eq!(widgets.focused(), Some(2));
+}
+
+#[test]
+fn keys_only_reach_focused_element() {
+    let mut screen = TextBufferScreen::new(24, 5);
+    let mut widgets = FocusManager::new();
+
+    widgets.push_pane(menu(vec!["Alpha", "Beta"]), (0, 0, 12, 5).into(), None);
+    widgets.push_pane(menu(vec!["Gamma", "Delta"]), (12, 0, 12, 5).into(), None);
+    widgets.draw(&mut screen);
+
+    // Menus start without a selection, so the first press selects the first item
+    assert!(widgets.handle_event(&InputEvent::key(Key::Down, KeyModifiers::NONE)));
+    assert!(widgets.handle_event(&InputEvent::key(Key::Down, KeyModifiers::NONE)));
+    assert!(!widgets.handle_event(&InputEvent::key(Key::Char('x'), KeyModifiers::NONE)));
+    widgets.draw(&mut screen);
+
+    let text = text_snapshot(&screen);
+    assert!(text.contains("Beta"));
+    assert!(!text.contains("Gamma") && !text.contains("Delta"));
+}
+
+#[test]
+fn focused_text_box_pages_with_keys() {
+    let mut screen = TextBufferScreen::new(10, 4);
+    let mut widgets = FocusManager::new();
+
+    let mut log = ui::TextBox::new((0, 0, 10, 3).into());
+    log.place_string("l1\nl2\nl3\nl4\nl5");
+    widgets.push(ui::GraphicalMenu::new(vec!["Menu"], (0, 3).into(), MenuSettings::new()));
+    let log = widgets.push(log);
+
+    assert!(widgets.handle_event(&InputEvent::key(Key::Tab, KeyModifiers::NONE)));
+    assert_eq!(widgets.focused(), Some(log));
+
+    widgets.draw(&mut screen);
+    assert!(text_snapshot(&screen).starts_with("l3\nl4\nl5\n"));
+
+    assert!(widgets.handle_event(&InputEvent::key(Key::PageUp, KeyModifiers::NONE)));
+    widgets.draw(&mut screen);
+    assert!(text_snapshot(&screen).starts_with("l1\nl2\nl3  more ▼\n"));
+
+    assert!(widgets.handle_event(&InputEvent::key(Key::End, KeyModifiers::NONE)));
+    widgets.draw(&mut screen);
+    assert!(text_snapshot(&screen).starts_with("l3\nl4\nl5\n"));
+}
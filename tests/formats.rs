@@ -0,0 +1,177 @@
+use asciiengine::formats::{AnsiArt, Sauce, XpCell, XpColors, XpImage, XpLayer};
+use asciiengine::screen::{CellAttributes, CellStyle, CharacterColor, Color, Palette, TextBufferPos, TextBufferScreen, VGAChar};
+use asciiengine::raster::RgbaImage;
+use asciiengine::snapshot::text_and_color_layers;
+
+#[test]
+fn rexpaint_round_trip() {
+    let mut screen = TextBufferScreen::new(6, 3);
+    screen.write_string((0, 0).into(), "Orc", CellStyle::new(CharacterColor::Red, CharacterColor::Blue)).unwrap();
+    screen.write_string((2, 2).into(), "@", CellStyle::from_colors(Color::Rgb(12, 34, 56), Color::Rgb(1, 2, 3))).unwrap();
+
+    let image = XpImage::from_screen(&screen);
+    let decoded = XpImage::decode(&image.encode().unwrap()).unwrap();
+    assert_eq!(decoded, image);
+
+    let loaded = decoded.to_screen(XpColors::Nearest(&Palette::VGA));
+    assert_eq!(loaded.char_ref((1, 0).into()).unwrap().style, CellStyle::new(CharacterColor::Red, CharacterColor::Blue));
+    assert_eq!(loaded.char_ref((1, 0).into()).unwrap().character, VGAChar::from_char('r').unwrap());
+
+    let exact = decoded.to_screen(XpColors::Exact);
+    assert_eq!(exact.char_ref((2, 2).into()).unwrap().style, CellStyle::from_colors(Color::Rgb(12, 34, 56), Color::Rgb(1, 2, 3)));
+}
+
+#[test]
+fn rexpaint_layers_composite() {
+    let mut bottom = XpLayer::new(3, 1);
+    let mut top = XpLayer::new(2, 1);
+
+    for x in 0..3 {
+        *bottom.get_mut(x, 0).unwrap() = XpCell { character: VGAChar(b'.'), foreground: (170, 170, 170), background: (0, 0, 0) };
+    }
+    *top.get_mut(1, 0).unwrap() = XpCell { character: VGAChar(b'@'), foreground: (255, 255, 85), background: (0, 0, 0) };
+
+    let image = XpImage { layers: vec![bottom, top] };
+    let decoded = XpImage::decode(&image.encode().unwrap()).unwrap();
+    assert_eq!(decoded.size(), (3, 1));
+
+    let screen = decoded.to_screen(XpColors::Nearest(&Palette::VGA));
+    let text: String = (0..3).map(|x| screen.char_ref((x, 0).into()).unwrap().character.to_char()).collect();
+    assert_eq!(text, ".@.");
+    assert_eq!(screen.char_ref((1, 0).into()).unwrap().style.foreground, Color::Named(CharacterColor::Yellow));
+
+    let mut target = TextBufferScreen::new(5, 2);
+    decoded.layers[1].blit(&mut target, TextBufferPos { x: 3, y: 1 }, XpColors::Exact);
+    assert_eq!(target.char_ref((4, 1).into()).unwrap().character, VGAChar(b'@'));
+    assert!(target.char_ref((3, 1).into()).unwrap().is_blank());
+}
+
+#[test]
+fn nearest_palette_color() {
+    assert_eq!(Palette::VGA.nearest((250, 80, 80)), CharacterColor::LightRed);
+    assert_eq!(Palette::VGA.nearest((0, 0, 0)), CharacterColor::Black);
+    assert_eq!(Palette::VGA.nearest((0, 0, 160)), CharacterColor::Blue);
+}
+
+#[test]
+fn ansi_reads_colors_and_cursor_movement() {
+    let art = AnsiArt::decode(b"\x1b[1;31mA\x1b[0;44mB\r\n\x1b[2CC\x1b[1;1H\x1b[33m\xdb");
+    let screen = &art.screen;
+
+    assert_eq!((screen.width(), screen.height()), (80, 2));
+    assert_eq!(screen.char_ref((0, 0).into()).unwrap().character, VGAChar(0xdb));
+    assert_eq!(screen.char_ref((0, 0).into()).unwrap().style.foreground, Color::Named(CharacterColor::Brown));
+    assert_eq!(screen.char_ref((1, 0).into()).unwrap().style, CellStyle::new(CharacterColor::White, CharacterColor::Blue));
+    assert_eq!(screen.char_ref((2, 1).into()).unwrap().character, VGAChar(b'C'));
+}
+
+#[test]
+fn ansi_sauce_round_trip() {
+    let mut screen = TextBufferScreen::new(4, 3);
+    screen.write_string((0, 0).into(), "Full", CellStyle::new(CharacterColor::Yellow, CharacterColor::Blue)).unwrap();
+    screen.write_string((1, 1).into(), "x", CellStyle::new(CharacterColor::Green, CharacterColor::LightRed)).unwrap();
+    screen.write_string((0, 2).into(), "-", CellStyle::new(CharacterColor::Gray, CharacterColor::Black).with(CellAttributes::UNDERLINE)).unwrap();
+
+    let mut sauce = Sauce::new(4, 3);
+    sauce.title = "Title Screen".to_string();
+    sauce.author = "Ash".to_string();
+    sauce.comments = vec!["Drawn for the test suite".to_string()];
+
+    let bytes = AnsiArt::encode_buffer(&screen, &Palette::VGA, Some(&sauce));
+    let art = AnsiArt::decode(&bytes);
+
+    let read_sauce = art.sauce.as_ref().unwrap();
+    assert_eq!(read_sauce.title, "Title Screen");
+    assert_eq!(read_sauce.comments, sauce.comments);
+    assert!(read_sauce.ice_colors());
+    assert_eq!(text_and_color_layers((4, 3), |x, y| *art.screen.char_ref((x as isize, y as isize).into()).unwrap()),
+        text_and_color_layers((4, 3), |x, y| *screen.char_ref((x as isize, y as isize).into()).unwrap()));
+
+    assert_eq!(AnsiArt::decode(&art.encode()).screen.char_ref((1, 1).into()), screen.char_ref((1, 1).into()));
+}
+
+#[test]
+fn ansi_full_width_row_before_empty_row() {
+    let mut screen = TextBufferScreen::new(4, 4);
+    screen.write_string((0, 0).into(), "Full", CellStyle::default()).unwrap();
+    screen.write_string((0, 2).into(), "ab", CellStyle::default()).unwrap();
+    screen.write_string((0, 3).into(), "Last", CellStyle::default()).unwrap();
+
+    let art = AnsiArt::decode(&AnsiArt::encode_buffer(&screen, &Palette::VGA, Some(&Sauce::new(4, 4))));
+    assert_eq!(text_and_color_layers((4, 4), |x, y| *art.screen.char_ref((x as isize, y as isize).into()).unwrap()),
+        text_and_color_layers((4, 4), |x, y| *screen.char_ref((x as isize, y as isize).into()).unwrap()));
+
+    // Without a SAUCE record the art is read at the default width, the line breaks still keep the rows apart
+    let art = AnsiArt::decode(&AnsiArt::encode_buffer(&screen, &Palette::VGA, None));
+    let row = |y: isize| -> String { (0..4).map(|x| art.screen.char_ref((x, y).into()).unwrap().character.to_char()).collect() };
+    assert_eq!([row(0), row(1), row(2), row(3)], ["Full", "    ", "ab  ", "Last"]);
+}
+
+#[test]
+fn ansi_bright_backgrounds_without_sauce() {
+    let mut screen = TextBufferScreen::new(3, 1);
+    screen.write_string((0, 0).into(), "hi", CellStyle::new(CharacterColor::Black, CharacterColor::LightCyan)).unwrap();
+
+    let art = AnsiArt::decode(&AnsiArt::encode_buffer(&screen, &Palette::VGA, None));
+    assert!(art.sauce.as_ref().unwrap().ice_colors());
+    assert_eq!(art.screen.char_ref((1, 0).into()), screen.char_ref((1, 0).into()));
+
+    // Plain colors do not need a record
+    let mut plain = TextBufferScreen::new(3, 1);
+    plain.write_string((0, 0).into(), "hi", CellStyle::new(CharacterColor::Yellow, CharacterColor::Blue)).unwrap();
+    assert!(AnsiArt::decode(&AnsiArt::encode_buffer(&plain, &Palette::VGA, None)).sauce.is_none());
+}
+
+#[test]
+fn ansi_clamps_sauce_dimensions() {
+    let mut sauce = Sauce::new(1, 1);
+    sauce.width = u16::MAX;
+    sauce.height = u16::MAX;
+
+    let mut bytes = b"hi".to_vec();
+    bytes.extend(sauce.encode(bytes.len()));
+
+    let art = AnsiArt::decode(&bytes);
+    assert_eq!((art.screen.width(), art.screen.height()), (1000, 10000));
+    assert_eq!(art.sauce.map(|s| (s.width, s.height)), Some((u16::MAX, u16::MAX)));
+}
+
+#[test]
+fn rexpaint_rejects_corrupt_headers() {
+    use std::io::Write;
+
+    let compress = |values: &[i32], cells: usize| {
+        let mut data: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        data.resize(data.len() + cells * 10, 0);
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(&data).unwrap();
+        encoder.finish().unwrap()
+    };
+
+    assert!(XpImage::decode(&compress(&[-1, 1, 2, 2], 4)).is_ok());
+    assert!(XpImage::decode(&compress(&[-1, 1, 2, 2], 3)).is_err());
+    assert!(XpImage::decode(&compress(&[-1, 1, -2, 2], 4)).is_err());
+    assert!(XpImage::decode(&compress(&[-1, -1], 0)).is_err());
+    assert!(XpImage::decode(&compress(&[-1, 1, i32::MAX, i32::MAX], 1)).is_err());
+}
+
+#[test]
+fn bmp_rejects_corrupt_headers() {
+    let header = |width: i32, height: i32, bits: u16, pixels: usize| {
+        let mut data = b"BM".to_vec();
+        data.resize(54 + pixels, 0);
+        data[10..14].copy_from_slice(&54u32.to_le_bytes());
+        data[14..18].copy_from_slice(&40u32.to_le_bytes());
+        data[18..22].copy_from_slice(&width.to_le_bytes());
+        data[22..26].copy_from_slice(&height.to_le_bytes());
+        data[28..30].copy_from_slice(&bits.to_le_bytes());
+        data
+    };
+
+    assert!(RgbaImage::decode_bmp(&header(2, 2, 24, 16)).is_ok());
+    assert!(RgbaImage::decode_bmp(&header(2, 2, 24, 15)).is_err());
+    assert!(RgbaImage::decode_bmp(&header(2, 2, 16, 16)).is_err());
+    assert!(RgbaImage::decode_bmp(&header(i32::MAX, i32::MAX, 24, 0)).is_err());
+    assert!(RgbaImage::decode_bmp(&header(i32::MIN, i32::MIN, 32, 0)).is_err());
+}
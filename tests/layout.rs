@@ -0,0 +1,92 @@
+use asciiengine::screen::{TextBufferRect, TextBufferScreen};
+use asciiengine::ui::{Constraint, Layout, ScreenLayout};
+
+#[test]
+fn fixed_percent_and_fill() {
+    let layout = Layout::horizontal()
+        .pane(Constraint::Fixed(10), "a")
+        .pane(Constraint::Percent(25), "b")
+        .pane(Constraint::Fill(1), "c")
+        .pane(Constraint::Fill(3), "d");
+
+    let rects = layout.compute(TextBufferRect::new(0, 0, 100, 5));
+
+    assert_eq!(rects["a"], TextBufferRect::new(0, 0, 10, 5));
+    assert_eq!(rects["b"], TextBufferRect::new(10, 0, 25, 5));
+    assert_eq!(rects["c"], TextBufferRect::new(35, 0, 17, 5));
+    assert_eq!(rects["d"], TextBufferRect::new(52, 0, 48, 5));
+}
+
+#[test]
+fn min_and_max() {
+    let layout = Layout::vertical()
+        .pane(Constraint::Max(3), "header")
+        .pane(Constraint::Min(10), "body")
+        .pane(Constraint::Max(2), "footer");
+
+    let rects = layout.compute(TextBufferRect::new(0, 0, 20, 40));
+    assert_eq!(rects["header"].height, 3);
+    assert_eq!(rects["body"].height, 35);
+    assert_eq!(rects["footer"], TextBufferRect::new(0, 38, 20, 2));
+
+    // The minimum is kept even when there is no space left for the others
+    let rects = layout.compute(TextBufferRect::new(0, 0, 20, 8));
+    assert_eq!(rects["body"].height, 8);
+    assert_eq!(rects["header"].height, 0);
+}
+
+#[test]
+fn nested_layouts_and_gaps() {
+    let layout = Layout::horizontal()
+        .split(Constraint::Percent(50), Layout::vertical()
+            .pane(Constraint::Percent(75), "pane0")
+            .pane(Constraint::Fill(1), "pane1"))
+        .gap(Constraint::Fixed(1))
+        .split(Constraint::Fill(1), Layout::vertical()
+            .pane(Constraint::Fill(1), "pane2")
+            .split(Constraint::Fill(1), Layout::horizontal()
+                .pane(Constraint::Fill(1), "pane3")
+                .pane(Constraint::Fill(1), "pane4")));
+
+    let rects = layout.compute(TextBufferRect::new(2, 1, 41, 20));
+
+    assert_eq!(rects["pane0"], TextBufferRect::new(2, 1, 20, 15));
+    assert_eq!(rects["pane1"], TextBufferRect::new(2, 16, 20, 5));
+    assert_eq!(rects["pane2"], TextBufferRect::new(23, 1, 20, 10));
+    assert_eq!(rects["pane3"], TextBufferRect::new(23, 11, 10, 10));
+    assert_eq!(rects["pane4"], TextBufferRect::new(33, 11, 10, 10));
+}
+
+#[test]
+fn screen_layout_recomputes_on_resize() {
+    let mut screen = TextBufferScreen::new(40, 10);
+    let mut layout = ScreenLayout::new(Layout::horizontal()
+        .pane(Constraint::Percent(50), "left")
+        .pane(Constraint::Fill(1), "right"));
+
+    assert!(layout.update(&screen));
+    assert!(!layout.update(&screen));
+    assert_eq!(layout["right"], TextBufferRect::new(20, 0, 20, 10));
+
+    screen.resize_buffer(61, 12);
+    assert!(layout.update(&screen));
+    assert_eq!(layout.rect("left"), Some(TextBufferRect::new(0, 0, 30, 12)));
+    assert_eq!(layout.rect("right"), Some(TextBufferRect::new(30, 0, 31, 12)));
+    assert_eq!(layout.rect("missing"), None);
+}
+
+#[test]
+fn rect_overlap_without_shared_corners() {
+    let inner = TextBufferRect::new(2, 2, 2, 2);
+    let outer = TextBufferRect::new(0, 0, 6, 6);
+    assert!(inner.overlap(&outer));
+    assert_eq!(inner.intersection(&outer), Some(inner));
+
+    // A cross where neither rect has a corner inside the other
+    let wide = TextBufferRect::new(0, 2, 6, 2);
+    let tall = TextBufferRect::new(2, 0, 2, 6);
+    assert!(wide.overlap(&tall));
+    assert_eq!(wide.intersection(&tall), Some(TextBufferRect::new(2, 2, 2, 2)));
+
+    assert!(!wide.overlap(&TextBufferRect::new(6, 2, 2, 2)));
+}
@@ -0,0 +1,74 @@
+use asciiengine::screen::{CellAttributes, CellStyle, CharacterColor, Color, Drawable, Markup, TextAlign, TextBufferScreen};
+use asciiengine::snapshot::text_snapshot;
+use asciiengine::ui::{TextBox, UIElement};
+
+const BASE: CellStyle = CellStyle::new(CharacterColor::White, CharacterColor::Black);
+
+fn foreground(screen: &TextBufferScreen, x: isize, y: isize) -> Color {
+    screen.char_ref((x, y).into()).unwrap().style.foreground
+}
+
+#[test]
+fn markup_colors_words() {
+    let markup = Markup::parse("You hit the [red]orc[/] for [yellow on blue]5[/]").unwrap();
+    let cells = markup.cells(BASE);
+
+    let text: String = cells.iter().map(|c| c.character.to_char()).collect();
+    assert_eq!(text, "You hit the orc for 5");
+
+    assert_eq!(cells[0].style, BASE);
+    assert_eq!(cells[12].style.foreground, Color::Named(CharacterColor::Red));
+    assert_eq!(cells[15].style, BASE);
+    assert_eq!(cells[20].style, CellStyle::new(CharacterColor::Yellow, CharacterColor::Blue));
+}
+
+#[test]
+fn markup_nests_and_escapes() {
+    let markup = Markup::parse("[[a] [light_green]b[u]c[#102030]d[/]e[/]f[/]g").unwrap();
+    let cells = markup.cells(BASE);
+
+    let text: String = cells.iter().map(|c| c.character.to_char()).collect();
+    assert_eq!(text, "[a] bcdefg");
+
+    let green = Color::Named(CharacterColor::LightGreen);
+    assert_eq!(cells[4].style.foreground, green);
+    assert_eq!(cells[5].style, BASE.foreground(green).with(CellAttributes::UNDERLINE));
+    assert_eq!(cells[6].style, BASE.foreground(Color::Rgb(0x10, 0x20, 0x30)).with(CellAttributes::UNDERLINE));
+    assert_eq!(cells[7].style, BASE.foreground(green).with(CellAttributes::UNDERLINE));
+    assert_eq!(cells[8].style.foreground, green);
+    assert_eq!(cells[9].style, BASE);
+
+    assert_eq!(Markup::escape("[x]"), "[[x]");
+    assert_eq!(Markup::parse(&Markup::escape("[x]")).unwrap().cells(BASE).len(), 3);
+}
+
+#[test]
+fn markup_rejects_bad_tags() {
+    assert!(Markup::parse("[purple]x").is_err());
+    assert!(Markup::parse("[red").is_err());
+    assert!(Markup::parse("x[/]").is_err());
+    assert!(Markup::parse("[on]x").is_err());
+    assert!(Markup::parse("5€").is_err());
+}
+
+#[test]
+fn markup_draws_to_screen_and_textbox() {
+    let mut screen = TextBufferScreen::new(12, 4);
+    let markup = Markup::parse("[red]a[/]b\nc[green]d").unwrap();
+
+    let rect = markup.draw(&mut screen, (12, 0).into(), &(BASE, TextAlign::Right).into());
+    assert_eq!(rect.height, 2);
+    assert_eq!(text_snapshot(&screen), "          ab\n          cd\n\n\n");
+    assert_eq!(foreground(&screen, 10, 0), Color::Named(CharacterColor::Red));
+    assert_eq!(foreground(&screen, 11, 1), Color::Named(CharacterColor::Green));
+
+    let mut screen = TextBufferScreen::new(12, 2);
+    let mut log = TextBox::new((0, 0, 12, 2).into());
+    log.set_color(CharacterColor::Cyan);
+    log.place_markup_str("[red]orc[/] hits\n[[you]").unwrap();
+    log.refresh(&mut screen);
+
+    assert_eq!(text_snapshot(&screen), "orc hits\n[you]\n");
+    assert_eq!(foreground(&screen, 0, 0), Color::Named(CharacterColor::Red));
+    assert_eq!(foreground(&screen, 4, 0), Color::Named(CharacterColor::Cyan));
+}
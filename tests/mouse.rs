@@ -0,0 +1,83 @@
+use asciiengine::input::{InputEvent, MouseButton, MouseEvent, MouseEventKind, MouseTracker};
+use asciiengine::screen::{CharacterColor, Color, TextAlign, TextBufferInterface, TextBufferScreen};
+use asciiengine::ui::{FocusManager, GraphicalMenu, MenuSettings, ScrollBox, UIElement};
+
+fn mouse(kind: MouseEventKind, x: isize, y: isize) -> InputEvent {
+    InputEvent::mouse(kind, (x, y).into())
+}
+
+#[test]
+fn tracker_converts_pixels_to_cells() {
+    let mut tracker = MouseTracker::new((9, 16), 200);
+
+    assert_eq!(tracker.pixel_to_cell(17, 31), (0, 0).into());
+    assert_eq!(tracker.pixel_to_cell(18, 32), (1, 1).into());
+    assert_eq!(tracker.pixel_to_cell(-1, -1), (-1, -1).into());
+
+    // Wheel events need to know where the pointer is
+    assert_eq!(tracker.wheel(0, 1), None);
+
+    assert_eq!(tracker.motion(40, 40), Some(mouse(MouseEventKind::Moved, 2, 1)));
+    assert_eq!(tracker.motion(41, 41), None);
+    assert_eq!(tracker.button(MouseButton::Left, true, 41, 41), Some(mouse(MouseEventKind::Pressed(MouseButton::Left), 2, 1)));
+    assert_eq!(tracker.motion(60, 41), Some(mouse(MouseEventKind::Dragged(MouseButton::Left), 3, 1)));
+    assert_eq!(tracker.button(MouseButton::Left, false, 60, 41), Some(mouse(MouseEventKind::Released(MouseButton::Left), 3, 1)));
+    assert_eq!(tracker.wheel(0, -2), Some(mouse(MouseEventKind::Wheel { x: 0, y: -2 }, 3, 1)));
+
+    tracker.set_scale((9, 16), 100);
+    assert_eq!(tracker.motion(60, 41), Some(mouse(MouseEventKind::Moved, 6, 2)));
+}
+
+#[test]
+fn menu_items_are_clickable_and_highlight_on_hover() {
+    let mut screen = TextBufferScreen::new(10, 4);
+    let mut menu = GraphicalMenu::new(vec!["One", "Two", "Three"], (1, 0).into(), MenuSettings::new());
+    menu.refresh(&mut screen);
+
+    assert!(menu.on_event(&mouse(MouseEventKind::Moved, 2, 1)));
+    assert_eq!(menu.hovered(), Some(1));
+    menu.refresh(&mut screen);
+    assert_eq!(screen.char_ref((1, 1).into()).unwrap().style.foreground, Color::Named(CharacterColor::LightCyan));
+
+    assert!(!menu.on_event(&mouse(MouseEventKind::Moved, 8, 1)));
+    assert_eq!(menu.hovered(), None);
+
+    assert!(menu.on_event(&mouse(MouseEventKind::Pressed(MouseButton::Left), 3, 2)));
+    assert_eq!(menu.menu().selected_index(), Some(2));
+    assert!(!menu.on_event(&mouse(MouseEventKind::Pressed(MouseButton::Left), 3, 3)));
+}
+
+#[test]
+fn focus_manager_routes_mouse_to_panes() {
+    let mut screen = TextBufferScreen::new(24, 6);
+    let mut widgets = FocusManager::new();
+
+    let mut scrollbox = ScrollBox::new((0, 0, 10, 4).into(), (10, 20));
+    scrollbox.set_bounded(true);
+    for y in 0..20 {
+        scrollbox.write_string((0, y).into(), &format!("row {}", y), CharacterColor::White.into()).unwrap();
+    }
+
+    let scroll_index = widgets.push_pane(scrollbox, (0, 0, 12, 6).into(), Some(("Scroll", TextAlign::Left)));
+    let menu_index = widgets.push_pane(GraphicalMenu::new(vec!["A", "B"], (0, 0).into(), MenuSettings::new()), (12, 0, 12, 6).into(), None);
+    widgets.draw(&mut screen);
+
+    assert_eq!(widgets.element_at((5, 2).into()), Some(scroll_index));
+    assert_eq!(widgets.element_at((13, 2).into()), Some(menu_index));
+    assert_eq!(widgets.element_at((0, 0).into()), None);
+
+    // The wheel scrolls the element under the mouse without taking focus from the others
+    assert!(widgets.handle_event(&mouse(MouseEventKind::Wheel { x: 0, y: -1 }, 5, 2)));
+    widgets.draw(&mut screen);
+    assert_eq!(screen.char_ref((1, 1).into()).unwrap().character.to_char(), 'r');
+    assert_eq!(screen.char_ref((5, 1).into()).unwrap().character.to_char(), '3');
+
+    assert_eq!(widgets.focused(), Some(scroll_index));
+    assert!(widgets.handle_event(&mouse(MouseEventKind::Pressed(MouseButton::Left), 13, 2)));
+    assert_eq!(widgets.focused(), Some(menu_index));
+
+    let menu = widgets.element(menu_index).unwrap();
+    assert!(menu.hit_test((0, 1).into()));
+    assert!(!menu.hit_test((1, 1).into()));
+    assert!(!widgets.handle_event(&InputEvent::Mouse(MouseEvent::new(MouseEventKind::Pressed(MouseButton::Left), (0, 0).into()))));
+}
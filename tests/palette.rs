@@ -0,0 +1,54 @@
+use asciiengine::screen::{CharacterColor, Color, Palette, TextBufferScreen};
+
+#[test]
+fn named_colors_resolve_through_the_palette() {
+    let red = Color::from(CharacterColor::Red);
+    let orange = Color::from((255, 128, 0));
+
+    assert_eq!(red.resolve(&Palette::VGA), (176, 0, 0));
+    assert_eq!(red.resolve(&Palette::CGA), (0xAA, 0x00, 0x00));
+    assert_eq!(Palette::SOLARIZED.resolve(&red), (0xDC, 0x32, 0x2F));
+
+    // Exact colors are the same in every palette
+    assert_eq!(orange, Color::Rgb(255, 128, 0));
+    assert_eq!(orange.resolve(&Palette::VGA), (255, 128, 0));
+    assert_eq!(orange.resolve(&Palette::GRAYSCALE), (255, 128, 0));
+}
+
+#[test]
+fn palettes_can_be_edited() {
+    let mut palette = Palette::default();
+    assert_eq!(palette, Palette::VGA);
+
+    palette.set(CharacterColor::Brown, (1, 2, 3));
+    assert_eq!(palette.get(CharacterColor::Brown), (1, 2, 3));
+    assert_eq!(palette.colors()[CharacterColor::Brown.index()], (1, 2, 3));
+    assert_eq!(palette.get(CharacterColor::Red), Palette::VGA.get(CharacterColor::Red));
+}
+
+#[test]
+fn nearest_named_color() {
+    for index in 0..16 {
+        let color = CharacterColor::from_index(index);
+        assert_eq!(color.index(), index);
+        assert_eq!(Palette::CGA.nearest(Palette::CGA.get(color)), color);
+    }
+
+    assert_eq!(Palette::VGA.nearest((250, 250, 250)), CharacterColor::BrightWhite);
+    assert_eq!(Palette::VGA.nearest((10, 0, 160)), CharacterColor::Blue);
+    assert_eq!(Palette::VGA.nearest((250, 90, 100)), CharacterColor::LightRed);
+}
+
+#[test]
+fn switching_palettes_redraws_the_screen() {
+    let mut screen = TextBufferScreen::new(5, 2);
+    assert_eq!(screen.palette(), &Palette::VGA);
+
+    screen.take_dirty();
+    screen.get_and_clear_optimization_flag();
+
+    screen.set_palette(Palette::CGA);
+    assert_eq!(screen.palette(), &Palette::CGA);
+    assert_eq!(screen.take_dirty(), vec![screen.screen_rect()]);
+    assert!(screen.get_and_clear_optimization_flag());
+}
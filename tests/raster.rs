@@ -0,0 +1,80 @@
+use asciiengine::raster::{RgbaImage, SoftwareFont};
+use asciiengine::screen::{CellAttributes, CellStyle, CharacterColor, Color, TextBufferScreen};
+
+fn codepage() -> SoftwareFont {
+    SoftwareFont::from_file(format!("{}/assets/codepage.bmp", env!("CARGO_MANIFEST_DIR")), (9, 16), (8, 8), 32).unwrap()
+}
+
+#[test]
+fn screenshot_dimensions_and_colors() {
+    let font = codepage();
+    let mut screen = TextBufferScreen::new(4, 2);
+
+    screen.write_string((0, 0).into(), "#", CellStyle::from_colors(Color::Rgb(255, 0, 0), Color::Rgb(0, 0, 255))).unwrap();
+    screen.write_string((1, 1).into(), " ", CellStyle::new(CharacterColor::White, CharacterColor::Green)).unwrap();
+
+    let image = screen.screenshot(&font, 100).unwrap();
+    assert_eq!((image.width(), image.height()), (36, 32));
+
+    // Every pixel of the first cell is either the blue background or the red glyph
+    let mut glyph_pixels = 0;
+    for y in 0..16 {
+        for x in 0..9 {
+            match image.get_pixel(x, y).unwrap() {
+                (0, 0, 255, 255) => (),
+                (r, 0, 0, 255) if r > 0 => glyph_pixels += 1,
+                p => panic!("unexpected pixel {:?} at {}, {}", p, x, y)
+            }
+        }
+    }
+    assert!(glyph_pixels > 0);
+
+    let green = screen.palette().get(CharacterColor::Green);
+    assert_eq!(image.get_pixel(13, 20), Some((green.0, green.1, green.2, 255)));
+
+    let black = screen.palette().get(CharacterColor::Black);
+    assert_eq!(image.get_pixel(35, 31), Some((black.0, black.1, black.2, 255)));
+
+    let scaled = screen.screenshot(&font, 200).unwrap();
+    assert_eq!((scaled.width(), scaled.height()), (72, 64));
+}
+
+#[test]
+fn bmp_round_trip() {
+    let mut image = RgbaImage::new(3, 2, (0, 0, 0, 255));
+    image.set_pixel(0, 0, (255, 0, 0, 255));
+    image.set_pixel(2, 1, (10, 20, 30, 255));
+
+    assert_eq!(RgbaImage::decode_bmp(&image.encode_bmp()).unwrap(), image);
+}
+
+#[test]
+fn png_structure() {
+    let image = RgbaImage::new(5, 3, (1, 2, 3, 255));
+    let png = image.encode_png().unwrap();
+
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 5);
+    assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 3);
+    assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    // The checksum of an empty IEND chunk is fixed
+    assert_eq!(&png[png.len() - 4..], &[0xAE, 0x42, 0x60, 0x82]);
+}
+
+#[test]
+fn png_rejects_empty_images() {
+    assert!(RgbaImage::new(0, 3, (0, 0, 0, 255)).encode_png().is_err());
+    assert!(RgbaImage::new(3, 0, (0, 0, 0, 255)).encode_png().is_err());
+    assert!(TextBufferScreen::new(0, 0).screenshot(&codepage(), 100).unwrap().encode_png().is_err());
+}
+
+#[test]
+fn screenshot_at_tiny_scales() {
+    let mut screen = TextBufferScreen::new(2, 1);
+    let style = CellStyle::new(CharacterColor::White, CharacterColor::Black).with(CellAttributes::UNDERLINE | CellAttributes::STRIKETHROUGH);
+    screen.write_string((0, 0).into(), "ab", style).unwrap();
+
+    let image = screen.screenshot(&codepage(), 1).unwrap();
+    assert_eq!((image.width(), image.height()), (0, 0));
+}
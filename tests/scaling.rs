@@ -0,0 +1,66 @@
+use asciiengine::character_map::GlyphLayout;
+use asciiengine::input::MouseTracker;
+use asciiengine::scaling::{ScaleMode, Viewport};
+
+const LAYOUT: GlyphLayout = GlyphLayout::new((9, 16), (8, 8), 32);
+
+#[test]
+fn integer_fit_letterboxes_whole_multiples() {
+    let mode = ScaleMode::IntegerFit { columns: 80, rows: 25 };
+
+    assert_eq!(mode.viewport((1920, 1080), 100, &LAYOUT), Viewport { grid: (80, 25), pixel_scale: 200, origin: (240, 140) });
+    assert_eq!(mode.viewport((720, 400), 100, &LAYOUT), Viewport { grid: (80, 25), pixel_scale: 100, origin: (0, 0) });
+    // Too small to fit even at the natural size, the grid is still drawn at 1x
+    assert_eq!(mode.viewport((640, 480), 100, &LAYOUT).pixel_scale, 100);
+}
+
+#[test]
+fn fixed_grid_stretches_and_fill_grows_the_grid() {
+    let fixed = ScaleMode::FixedGrid { columns: 80, rows: 25 };
+    assert_eq!(fixed.viewport((1920, 1080), 100, &LAYOUT), Viewport { grid: (80, 25), pixel_scale: 266, origin: (2, 8) });
+
+    let fill = ScaleMode::Fill { pixel_scale: 100 };
+    assert_eq!(fill.viewport((640, 480), 100, &LAYOUT), Viewport { grid: (71, 30), pixel_scale: 100, origin: (0, 0) });
+    // A high density display doubles the drawable pixels, so cells are drawn twice as large to keep their size on screen
+    assert_eq!(fill.viewport((1280, 960), 200, &LAYOUT), Viewport { grid: (71, 30), pixel_scale: 200, origin: (0, 0) });
+    assert_eq!(ScaleMode::Fill { pixel_scale: 150 }.viewport((640, 480), 100, &LAYOUT).grid, (47, 20));
+}
+
+#[test]
+fn fractional_scales_leave_no_gaps_between_cells() {
+    let mut right = 0;
+    for x in 0..20 {
+        let (left, _, width, height) = LAYOUT.get_dest_rect(x, 0, 150);
+        assert_eq!(left, right);
+        assert!(width == 13 || width == 14);
+        assert_eq!(height, 24);
+        right = left + width as isize;
+    }
+
+    assert_eq!(LAYOUT.grid_size(20, 2, 150), (right as usize, 48));
+    assert_eq!(LAYOUT.get_dest_rect(3, 2, 200), (54, 64, 18, 32));
+}
+
+#[test]
+fn mouse_follows_the_viewport() {
+    let viewport = ScaleMode::FixedGrid { columns: 80, rows: 25 }.viewport((1920, 1080), 100, &LAYOUT);
+    let mut mouse = MouseTracker::default();
+    mouse.set_viewport(LAYOUT.character_size(), &viewport, 100);
+
+    // Every pixel maps to the cell drawn over it
+    for x in 0..200 {
+        let cell = mouse.pixel_to_cell(x + viewport.origin.0, viewport.origin.1).x;
+        let (left, _, width, _) = LAYOUT.get_dest_rect(cell, 0, viewport.pixel_scale);
+        assert!((left..left + width as isize).contains(&(x as isize)));
+    }
+
+    assert_eq!(mouse.pixel_to_cell(0, 0), (-1, -1).into());
+
+    // Window pixels are half the size of drawable pixels on a high density display
+    let viewport = ScaleMode::IntegerFit { columns: 10, rows: 10 }.viewport((200, 200), 200, &LAYOUT);
+    mouse.set_viewport(LAYOUT.character_size(), &viewport, 200);
+    assert_eq!(viewport.origin, (55, 20));
+    assert_eq!(mouse.pixel_to_cell(30, 12), (0, 0).into());
+    assert_eq!(mouse.pixel_to_cell(33, 28), (1, 2).into());
+    assert_eq!(mouse.pixel_to_cell(27, 9), (-1, -1).into());
+}
@@ -0,0 +1,167 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use asciiengine::input::{InputEvent, Key, KeyModifiers};
+use asciiengine::scene::{Backdrop, Scene, SceneStack, Transition};
+use asciiengine::screen::{Cell, CellAttributes, TextBufferRect, TextBufferScreen};
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+/// Fills a rect with a letter, counting the frames it actually drew and logging everything else
+struct Fill {
+    name: char,
+    rect: TextBufferRect,
+    backdrop: Backdrop,
+    log: Log,
+    draws: Rc<RefCell<usize>>,
+    /// Set from outside the stack to make the scene draw again
+    poke: Rc<RefCell<bool>>,
+    dirty: bool
+}
+
+impl Fill {
+    fn new(name: char, rect: TextBufferRect, backdrop: Backdrop, log: &Log) -> Self {
+        Self { name, rect, backdrop, log: log.clone(), draws: Rc::new(RefCell::new(0)), poke: Rc::new(RefCell::new(false)), dirty: true }
+    }
+
+    fn record(&self, what: &str) {
+        self.log.borrow_mut().push(format!("{} {}", self.name, what));
+    }
+}
+
+impl Scene for Fill {
+    fn handle_event(&mut self, event: &InputEvent) -> Transition {
+        self.record("event");
+
+        match event {
+            InputEvent::KeyDown { key: Key::Char('p'), .. } => Transition::push(Fill::new('p', (1, 1, 2, 1).into(), Backdrop::Dimmed, &self.log)),
+            InputEvent::KeyDown { key: Key::Char('t'), .. } => Transition::replace(Fill::new('t', (0, 0, 4, 3).into(), Backdrop::Hidden, &self.log)),
+            InputEvent::KeyDown { key: Key::Escape, .. } => Transition::Pop,
+            InputEvent::Quit => Transition::Quit,
+            _ => Transition::None
+        }
+    }
+
+    fn update(&mut self, _dt: Duration) -> Transition {
+        self.record("update");
+        self.dirty = true;
+        Transition::None
+    }
+
+    fn render(&mut self, screen: &mut TextBufferScreen) {
+        if self.dirty || self.poke.replace(false) {
+            screen.fill_rect(self.rect, Cell::new(self.name.try_into().unwrap(), Default::default()));
+            *self.draws.borrow_mut() += 1;
+            self.dirty = false;
+        }
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn backdrop(&self) -> Backdrop {
+        self.backdrop
+    }
+
+    fn on_enter(&mut self) {
+        self.record("enter");
+    }
+
+    fn on_exit(&mut self) {
+        self.record("exit");
+    }
+
+    fn on_pause(&mut self) {
+        self.record("pause");
+    }
+
+    fn on_resume(&mut self) {
+        self.record("resume");
+    }
+}
+
+fn key(c: char) -> InputEvent {
+    InputEvent::key(Key::Char(c), KeyModifiers::NONE)
+}
+
+fn cell(screen: &TextBufferScreen, x: isize, y: isize) -> (char, bool) {
+    let cell = screen.char_ref((x, y).into()).unwrap();
+    (cell.character.to_char(), cell.style.attributes.contains(CellAttributes::DIM))
+}
+
+#[test]
+fn overlays_dim_the_scenes_below_and_only_the_top_gets_input() {
+    let log = Log::default();
+    let mut screen = TextBufferScreen::new(4, 3);
+    let mut stack = SceneStack::new(Fill::new('g', (0, 0, 4, 3).into(), Backdrop::Hidden, &log));
+
+    stack.render(&mut screen);
+    assert_eq!(cell(&screen, 0, 0), ('g', false));
+
+    stack.handle_event(&key('p'));
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.top().unwrap().backdrop(), Backdrop::Dimmed);
+
+    stack.render(&mut screen);
+    assert_eq!(cell(&screen, 0, 0), ('g', true));
+    assert_eq!(cell(&screen, 1, 1), ('p', false));
+    assert_eq!(cell(&screen, 3, 1), ('g', true));
+
+    stack.update(Duration::from_millis(10));
+    stack.handle_event(&key('x'));
+    stack.handle_event(&InputEvent::key(Key::Escape, KeyModifiers::NONE));
+    assert_eq!(stack.len(), 1);
+
+    stack.render(&mut screen);
+    assert_eq!(cell(&screen, 0, 0), ('g', false));
+    assert_eq!(cell(&screen, 1, 1), ('g', false));
+
+    stack.handle_event(&key('t'));
+    stack.handle_event(&InputEvent::Quit);
+    assert!(stack.is_empty());
+
+    assert_eq!(*log.borrow(), vec![
+        "g enter", "g event", "g pause", "p enter",
+        "p update", "p event", "p event", "p exit", "g resume",
+        "g event", "g exit", "t enter", "t event", "t exit"
+    ]);
+}
+
+#[test]
+fn lower_scene_changes_redraw_the_overlay_and_idle_frames_stay_clean() {
+    let log = Log::default();
+    let mut screen = TextBufferScreen::new(4, 3);
+    let game = Fill::new('g', (0, 0, 4, 3).into(), Backdrop::Hidden, &log);
+    let popup = Fill::new('h', (0, 2, 4, 1).into(), Backdrop::Shown, &log);
+    let popup_draws = popup.draws.clone();
+    let game_draws = game.draws.clone();
+    let game_poke = game.poke.clone();
+
+    let mut stack = SceneStack::new(game);
+    stack.push(popup);
+    stack.render(&mut screen);
+    assert_eq!((*game_draws.borrow(), *popup_draws.borrow()), (1, 1));
+    assert_eq!(cell(&screen, 0, 0), ('g', false));
+    assert_eq!(cell(&screen, 0, 2), ('h', false));
+
+    screen.take_dirty();
+    screen.get_and_clear_optimization_flag();
+    stack.render(&mut screen);
+    assert!(screen.dirty_regions().is_empty());
+    assert_eq!(*popup_draws.borrow(), 1);
+
+    // The scene below draws over the popup, so the popup has to draw again on top of it
+    *game_poke.borrow_mut() = true;
+    stack.render(&mut screen);
+    assert_eq!((*game_draws.borrow(), *popup_draws.borrow()), (2, 2));
+    assert_eq!(cell(&screen, 0, 2), ('h', false));
+
+    let mut hidden = SceneStack::new(Fill::new('g', (0, 0, 4, 3).into(), Backdrop::Hidden, &log));
+    hidden.push(Fill::new('t', (0, 0, 1, 1).into(), Backdrop::Hidden, &log));
+    let mut screen = TextBufferScreen::new(4, 3);
+    hidden.render(&mut screen);
+    assert_eq!(cell(&screen, 0, 0), ('t', false));
+    assert_eq!(cell(&screen, 1, 0), (' ', false));
+}
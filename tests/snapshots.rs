@@ -0,0 +1,99 @@
+use asciiengine::backend::HeadlessBackend;
+use asciiengine::screen::{CharacterColor, TextAlign, TextBufferInterface, TextBufferScreen, TextView};
+use asciiengine::snapshot::{assert_color_snapshot, assert_matches_golden, assert_snapshot, text_snapshot};
+use asciiengine::ui::{self, MenuSettings, UIElement};
+
+fn golden(name: &str) -> String {
+    format!("{}/tests/snapshots/{}.txt", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+#[test]
+fn draw_box_titles() {
+    let mut screen = TextBufferScreen::new(24, 9);
+
+    ui::draw_box((0, 0, 24, 3).into(), &mut screen, Some(("Left", TextAlign::Left)));
+    ui::draw_box((0, 3, 24, 3).into(), &mut screen, Some(("Center", TextAlign::Center)));
+    ui::draw_box((0, 6, 12, 3).into(), &mut screen, Some(("A Long Title", TextAlign::Right)));
+    ui::draw_box((12, 6, 12, 3).into(), &mut screen, None);
+
+    assert_snapshot(&screen, golden("draw_box_titles"));
+}
+
+#[test]
+fn graphical_menu_selection() {
+    let mut screen = TextBufferScreen::new(12, 4);
+    let mut menu = ui::GraphicalMenu::new(vec!["New", "Load", "Options", "Quit"], (1, 0).into(), MenuSettings::new().reverse_selection());
+
+    menu.next();
+    menu.next();
+    menu.ui_draw(&mut screen);
+
+    assert_color_snapshot(&screen, golden("graphical_menu_selection"));
+}
+
+#[test]
+fn scrollbox_in_view() {
+    let mut screen = TextBufferScreen::new(16, 6);
+    let mut scrollbox = ui::ScrollBox::new((0, 0, 14, 4).into(), (30, 10));
+
+    for y in 0..10 {
+        scrollbox.write_string((0, y).into(), &format!("Line {} of the scroll box", y), CharacterColor::White.into()).unwrap();
+    }
+
+    scrollbox.scroll_vertical(3);
+
+    ui::draw_box(screen.screen_rect(), &mut screen, None);
+    let mut view = TextView::new(screen.screen_rect().interior(), &mut screen);
+    scrollbox.ui_draw(&mut view);
+
+    assert_snapshot(&view, golden("scrollbox_in_view"));
+}
+
+#[test]
+fn headless_backend_matches_buffer() {
+    let mut screen = TextBufferScreen::new(10, 3);
+    let mut backend = HeadlessBackend::new();
+
+    screen.write_string((1, 1).into(), "Hello", CharacterColor::Yellow.into()).unwrap();
+    screen.render(&mut backend).unwrap();
+
+    screen.write_string((4, 1).into(), "p!", CharacterColor::LightRed.into()).unwrap();
+    screen.render(&mut backend).unwrap();
+
+    assert_eq!(backend.frames(), 2);
+    assert_eq!(backend.to_text(), text_snapshot(&screen));
+    assert_matches_golden(&backend.to_text_with_colors(), golden("headless_backend_matches_buffer"));
+}
+
+#[test]
+fn heterogeneous_widget_list() {
+    let mut screen = TextBufferScreen::new(20, 6);
+
+    let mut scrollbox = ui::ScrollBox::new((0, 0, 20, 2).into(), (20, 4));
+    scrollbox.write_string((0, 0).into(), "Scroll box", CharacterColor::White.into()).unwrap();
+
+    let mut textbox = ui::TextBox::new((0, 2, 20, 2).into());
+    textbox.place_string("Text box");
+
+    let mut widgets: Vec<Box<dyn UIElement>> = vec![
+        Box::new(scrollbox),
+        Box::new(textbox),
+        Box::new(ui::GraphicalMenu::new(vec!["Menu"], (0, 5).into(), MenuSettings::new()))
+    ];
+
+    for widget in widgets.iter_mut() {
+        assert!(widget.refresh(&mut screen));
+    }
+
+    assert_snapshot(&screen, golden("heterogeneous_widget_list"));
+}
+
+#[test]
+#[should_panic(expected = "is missing")]
+fn missing_golden_fails() {
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        panic!("snapshot is missing, skipped while updating");
+    }
+
+    assert_matches_golden("text\n", golden("does_not_exist"));
+}
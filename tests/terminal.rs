@@ -0,0 +1,93 @@
+#![cfg(unix)]
+
+use asciiengine::input::{parse_input, InputEvent, Key, KeyModifiers};
+
+fn key(key: Key, modifiers: KeyModifiers) -> InputEvent {
+    InputEvent::key(key, modifiers)
+}
+
+#[test]
+fn arrows_and_csi_sequences() {
+    let (events, consumed) = parse_input(b"\x1b[A\x1b[1;5C\x1bOP\x1b[3~\x1b[15;2~\x1b[Z");
+
+    assert_eq!(consumed, 26);
+    assert_eq!(events, vec![
+        key(Key::Up, KeyModifiers::NONE),
+        key(Key::Right, KeyModifiers::CTRL),
+        key(Key::Function(1), KeyModifiers::NONE),
+        key(Key::Delete, KeyModifiers::NONE),
+        key(Key::Function(5), KeyModifiers::SHIFT),
+        key(Key::Tab, KeyModifiers::SHIFT)
+    ]);
+}
+
+#[test]
+fn control_and_alt_keys() {
+    let (events, consumed) = parse_input(b"\x03\x1bx\x1bA\r\x7f");
+
+    assert_eq!(consumed, 7);
+    assert_eq!(events, vec![
+        key(Key::Char('c'), KeyModifiers::CTRL),
+        key(Key::Char('x'), KeyModifiers::ALT),
+        key(Key::Char('a'), KeyModifiers { shift: true, ctrl: false, alt: true }),
+        key(Key::Enter, KeyModifiers::NONE),
+        key(Key::Backspace, KeyModifiers::NONE)
+    ]);
+}
+
+#[test]
+fn multibyte_text() {
+    let (events, consumed) = parse_input("é€😀".as_bytes());
+
+    assert_eq!(consumed, 9);
+    assert_eq!(events, vec![
+        key(Key::Char('é'), KeyModifiers::NONE),
+        InputEvent::Text("é".to_string()),
+        key(Key::Char('€'), KeyModifiers::NONE),
+        InputEvent::Text("€".to_string()),
+        key(Key::Char('😀'), KeyModifiers::NONE),
+        InputEvent::Text("😀".to_string())
+    ]);
+
+    // Alt with a multibyte character is decoded as one key
+    let (events, consumed) = parse_input("\x1bé".as_bytes());
+    assert_eq!(consumed, 3);
+    assert_eq!(events, vec![key(Key::Char('é'), KeyModifiers::ALT)]);
+}
+
+#[test]
+fn invalid_bytes_are_skipped_one_at_a_time() {
+    // A stray continuation byte and a lead byte followed by ascii
+    let (events, consumed) = parse_input(b"\x80a\xe2b");
+
+    assert_eq!(consumed, 4);
+    assert_eq!(events, vec![
+        key(Key::Char('a'), KeyModifiers::NONE),
+        InputEvent::Text("a".to_string()),
+        key(Key::Char('b'), KeyModifiers::NONE),
+        InputEvent::Text("b".to_string())
+    ]);
+
+    let (events, consumed) = parse_input(b"\x1b\xffq");
+    assert_eq!(consumed, 3);
+    assert_eq!(events, vec![
+        key(Key::Escape, KeyModifiers::NONE),
+        key(Key::Char('q'), KeyModifiers::NONE),
+        InputEvent::Text("q".to_string())
+    ]);
+}
+
+#[test]
+fn partial_sequences_are_left_unconsumed() {
+    let bytes = "a€".as_bytes();
+    let (events, consumed) = parse_input(&bytes[..2]);
+    assert_eq!(consumed, 1);
+    assert_eq!(events.len(), 2);
+
+    assert_eq!(parse_input(b"\x1b[1;5").1, 0);
+    assert_eq!(parse_input(b"\x1bO").1, 0);
+    assert_eq!(parse_input(&"\x1b€".as_bytes()[..3]).1, 0);
+
+    // A lone escape is the escape key
+    assert_eq!(parse_input(b"\x1b"), (vec![key(Key::Escape, KeyModifiers::NONE)], 1));
+}
@@ -0,0 +1,503 @@
+use asciiengine::input::{InputEvent, Key, KeyModifiers, MouseEventKind};
+use asciiengine::screen::{CellAttributes, CellStyle, CharacterColor, Color, TextAlign, TextBufferInterface, TextBufferScreen};
+use asciiengine::snapshot::text_snapshot;
+use asciiengine::ui::{DisplayBox, DisplayBoxSettings, ScrollBox, SpinboxSettings, TextArea, TextAreaSettings, TextBox, TextInput, TextInputSettings, Threshold, UIElement, ValueSpinbox};
+
+#[test]
+fn spinbox_clamps_and_wraps() {
+    let mut spinbox = ValueSpinbox::new(250u8, 0, 255, 10, (0, 0).into(), SpinboxSettings::new());
+
+    spinbox.increment();
+    assert_eq!(spinbox.value(), 255);
+    spinbox.increment();
+    assert_eq!(spinbox.value(), 255);
+
+    let mut wrapping = ValueSpinbox::new(5i32, -10, 10, 5, (0, 0).into(), SpinboxSettings::new().wrapping());
+    wrapping.increment();
+    wrapping.increment();
+    assert_eq!(wrapping.value(), -10);
+    wrapping.decrement();
+    assert_eq!(wrapping.value(), 10);
+
+    let clamped = ValueSpinbox::new(99.0f32, 0.0, 1.0, 0.1, (0, 0).into(), SpinboxSettings::new());
+    assert_eq!(clamped.value(), 1.0);
+}
+
+#[test]
+fn spinbox_fixed_point_and_units() {
+    let volume = ValueSpinbox::new(-125i32, -1000, 1000, 25, (0, 0).into(), SpinboxSettings::new().fixed_point(2).unit("dB"));
+    assert_eq!(volume.value_text(), "-1.25 dB");
+
+    let scale = ValueSpinbox::new(1.5f64, 0.5, 4.0, 0.25, (0, 0).into(), SpinboxSettings::new().fixed_point(1).unit("x"));
+    assert_eq!(scale.value_text(), "1.5 x");
+
+    let plain = ValueSpinbox::new(7usize, 0, 9, 1, (0, 0).into(), SpinboxSettings::new().fixed_point(0));
+    assert_eq!(plain.value_text(), "7");
+}
+
+#[test]
+fn spinbox_draws_and_handles_keys() {
+    let mut screen = TextBufferScreen::new(16, 1);
+    let mut spinbox = ValueSpinbox::new(50u32, 0, 100, 5, (1, 0).into(), SpinboxSettings::new().unit("%"));
+
+    assert!(spinbox.refresh(&mut screen));
+    assert_eq!(text_snapshot(&screen), " ◄ 50 % ►\n");
+    assert!(!spinbox.refresh(&mut screen));
+
+    assert!(spinbox.on_event(&InputEvent::key(Key::Left, KeyModifiers::NONE)));
+    assert!(spinbox.on_event(&InputEvent::key(Key::Left, KeyModifiers::NONE)));
+    assert!(!spinbox.on_event(&InputEvent::key(Key::Enter, KeyModifiers::NONE)));
+    assert!(spinbox.refresh(&mut screen));
+    assert_eq!(text_snapshot(&screen), " ◄ 40 % ►\n");
+
+    assert!(spinbox.on_event(&InputEvent::key(Key::End, KeyModifiers::NONE)));
+    spinbox.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), " ◄ 100 % ►\n");
+
+    // Shrinking text is cleared by clear_last
+    spinbox.set_value(0);
+    spinbox.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), " ◄ 0 % ►\n");
+}
+
+#[test]
+fn display_box_redraws_only_on_change() {
+    let mut screen = TextBufferScreen::new(12, 3);
+    let mut hp = DisplayBox::new((0, 0, 12, 3).into(), "HP", 100, DisplayBoxSettings::new().align(TextAlign::Right))
+        .threshold(Threshold::Below(25, CellStyle::new(CharacterColor::LightRed, CharacterColor::Black)))
+        .threshold(Threshold::Below(50, CellStyle::new(CharacterColor::Yellow, CharacterColor::Black)));
+
+    assert!(hp.refresh(&mut screen));
+    assert_eq!(text_snapshot(&screen), "┌┤HP├──────┐\n│       100│\n└──────────┘\n");
+
+    hp.set_value(100);
+    assert!(!hp.refresh(&mut screen));
+
+    hp.set_value(40);
+    assert!(hp.refresh(&mut screen));
+    assert_eq!(text_snapshot(&screen), "┌┤HP├──────┐\n│        40│\n└──────────┘\n");
+    assert_eq!(screen.char_ref((10, 1).into()).unwrap().style.foreground, Color::Named(CharacterColor::Yellow));
+
+    hp.set_value(7);
+    hp.refresh(&mut screen);
+    assert_eq!(screen.char_ref((10, 1).into()).unwrap().style.foreground, Color::Named(CharacterColor::LightRed));
+}
+
+#[test]
+fn display_box_truncates_values() {
+    let mut screen = TextBufferScreen::new(10, 3);
+    let mut name = DisplayBox::new((0, 0, 10, 3).into(), "Name", "Bartholomew", DisplayBoxSettings::new());
+
+    name.refresh(&mut screen);
+    assert_eq!(name.value_text(), "Barth...");
+    assert_eq!(text_snapshot(&screen).lines().nth(1), Some("│Barth...│"));
+
+    name.mut_settings().ellipsis = false;
+    assert_eq!(name.value_text(), "Bartholo");
+}
+
+#[test]
+fn display_box_truncates_labels_by_character() {
+    let mut screen = TextBufferScreen::new(10, 3);
+    let mut temperature = DisplayBox::new((0, 0, 10, 3).into(), "Température", 20, DisplayBoxSettings::new());
+
+    temperature.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen).lines().next(), Some("┌┤Tem...├┐"));
+
+    // Boxes too narrow for a shortened label are drawn without one
+    let mut screen = TextBufferScreen::new(6, 3);
+    let mut hp = DisplayBox::new((0, 0, 6, 3).into(), "Hit points", 7, DisplayBoxSettings::new());
+    hp.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "┌────┐\n│7   │\n└────┘\n");
+
+    hp.set_label("HP");
+    hp.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen).lines().next(), Some("┌┤HP├┐"));
+
+    // The smallest boxes are only a border, or nothing at all
+    let mut screen = TextBufferScreen::new(3, 2);
+    let mut corners = DisplayBox::new((0, 0, 2, 2).into(), "é", 1, DisplayBoxSettings::new());
+    let mut tiny = DisplayBox::new((2, 0, 1, 1).into(), "é", 1, DisplayBoxSettings::new());
+    corners.refresh(&mut screen);
+    tiny.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "┌┐\n└┘\n");
+}
+
+fn type_text(input: &mut TextInput, text: &str) {
+    input.on_event(&InputEvent::Text(text.to_string()));
+}
+
+#[test]
+fn text_input_edits_and_selects() {
+    let mut input = TextInput::new((0, 0, 10, 1).into(), TextInputSettings::new());
+
+    type_text(&mut input, "hello");
+    assert_eq!(input.text(), "hello");
+    assert_eq!(input.cursor(), 5);
+
+    input.on_event(&InputEvent::key(Key::Left, KeyModifiers::NONE));
+    input.on_event(&InputEvent::key(Key::Backspace, KeyModifiers::NONE));
+    assert_eq!(input.text(), "helo");
+
+    input.on_event(&InputEvent::key(Key::Home, KeyModifiers::NONE));
+    input.on_event(&InputEvent::key(Key::Delete, KeyModifiers::NONE));
+    assert_eq!(input.text(), "elo");
+
+    input.on_event(&InputEvent::key(Key::End, KeyModifiers::SHIFT));
+    assert_eq!(input.selected_text(), "elo");
+    type_text(&mut input, "yo");
+    assert_eq!(input.text(), "yo");
+    assert_eq!(input.selection(), None);
+
+    input.on_event(&InputEvent::key(Key::Char('a'), KeyModifiers::CTRL));
+    input.on_event(&InputEvent::key(Key::Backspace, KeyModifiers::NONE));
+    assert_eq!(input.text(), "");
+
+    // Key presses which produce text are swallowed so they are not inserted twice
+    assert!(input.on_event(&InputEvent::key(Key::Char('q'), KeyModifiers::NONE)));
+    assert_eq!(input.text(), "");
+}
+
+#[test]
+fn text_input_rejects_invalid_text() {
+    let mut input = TextInput::new((0, 0, 10, 1).into(), TextInputSettings::new().max_length(4))
+        .validator(|text| text.chars().all(|c| c.is_ascii_digit()));
+
+    type_text(&mut input, "12a3");
+    assert_eq!(input.text(), "");
+
+    type_text(&mut input, "123");
+    type_text(&mut input, "45");
+    assert_eq!(input.text(), "123");
+    type_text(&mut input, "4");
+    assert_eq!(input.text(), "1234");
+    type_text(&mut input, "5");
+    assert_eq!(input.text(), "1234");
+
+    // Text which is refused is not reported as handled
+    assert!(!input.on_event(&InputEvent::Text("5".to_string())));
+    input.on_event(&InputEvent::key(Key::Backspace, KeyModifiers::NONE));
+    assert!(!input.on_event(&InputEvent::Text("x".to_string())));
+    assert!(input.on_event(&InputEvent::Text("9".to_string())));
+    assert_eq!(input.text(), "1239");
+
+    let mut free = TextInput::new((0, 0, 10, 1).into(), TextInputSettings::new());
+    type_text(&mut free, "a€b");
+    assert_eq!(free.text(), "ab");
+    assert!(!free.insert_str("\u{1F600}"));
+    assert!(!free.on_event(&InputEvent::Text("\u{1F600}".to_string())));
+}
+
+#[test]
+fn text_input_scrolls_and_draws() {
+    let mut screen = TextBufferScreen::new(6, 1);
+    let mut input = TextInput::new((0, 0, 6, 1).into(), TextInputSettings::new()).placeholder("Name");
+
+    input.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "Name\n");
+
+    input.set_focused(true);
+    type_text(&mut input, "abcdefgh");
+    assert_eq!(input.scroll(), 3);
+    input.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "defgh\n");
+    assert!(screen.char_ref((5, 0).into()).unwrap().style.attributes.contains(CellAttributes::REVERSE));
+
+    input.on_event(&InputEvent::key(Key::Home, KeyModifiers::NONE));
+    assert_eq!(input.scroll(), 0);
+    input.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "abcdef\n");
+    assert!(screen.char_ref((0, 0).into()).unwrap().style.attributes.contains(CellAttributes::REVERSE));
+}
+
+fn press(element: &mut impl UIElement, key: Key, modifiers: KeyModifiers) {
+    element.on_event(&InputEvent::key(key, modifiers));
+}
+
+#[test]
+fn text_area_edits_lines() {
+    let mut area = TextArea::new((0, 0, 20, 5).into(), TextAreaSettings::new());
+
+    assert!(area.on_event(&InputEvent::Text("first".to_string())));
+    assert!(!area.on_event(&InputEvent::Text("\u{1F600}".to_string())));
+    press(&mut area, Key::Enter, KeyModifiers::NONE);
+    area.on_event(&InputEvent::Text("second".to_string()));
+    assert_eq!(area.text(), "first\nsecond");
+    assert_eq!(area.cursor(), (1, 6));
+
+    press(&mut area, Key::Home, KeyModifiers::NONE);
+    press(&mut area, Key::Backspace, KeyModifiers::NONE);
+    assert_eq!(area.text(), "firstsecond");
+    assert_eq!(area.cursor(), (0, 5));
+
+    press(&mut area, Key::Right, KeyModifiers::SHIFT);
+    press(&mut area, Key::Right, KeyModifiers::SHIFT);
+    assert_eq!(area.selected_text(), "se");
+    area.on_event(&InputEvent::Text("\n".to_string()));
+    press(&mut area, Key::Enter, KeyModifiers::NONE);
+    assert_eq!(area.text(), "first\ncond");
+
+    press(&mut area, Key::Up, KeyModifiers::NONE);
+    press(&mut area, Key::End, KeyModifiers::SHIFT);
+    press(&mut area, Key::Delete, KeyModifiers::NONE);
+    press(&mut area, Key::Delete, KeyModifiers::NONE);
+    assert_eq!(area.text(), "cond");
+}
+
+#[test]
+fn text_area_undo_and_redo() {
+    let mut area = TextArea::new((0, 0, 20, 5).into(), TextAreaSettings::new());
+
+    for c in "hello".chars() {
+        area.on_event(&InputEvent::Text(c.to_string()));
+    }
+    press(&mut area, Key::Enter, KeyModifiers::NONE);
+    area.insert_str("world");
+    assert_eq!(area.text(), "hello\nworld");
+
+    assert!(area.undo());
+    assert_eq!(area.text(), "hello\n");
+    assert!(area.undo());
+    assert_eq!(area.text(), "hello");
+    // Consecutive typed characters are undone together
+    assert!(area.undo());
+    assert_eq!(area.text(), "");
+    assert!(!area.undo());
+
+    press(&mut area, Key::Char('y'), KeyModifiers::CTRL);
+    assert_eq!(area.text(), "hello");
+    press(&mut area, Key::Char('z'), KeyModifiers::CTRL);
+    assert_eq!(area.text(), "");
+
+    area.redo();
+    area.insert_str("!");
+    assert!(!area.can_redo());
+}
+
+#[test]
+fn text_area_round_trips_code_page_437() {
+    let mut area = TextArea::new((0, 0, 20, 5).into(), TextAreaSettings::new());
+    let text = "╔═╗ café ½°\n║☺║ ░▒▓█\n\n╚═╝ αßΓπ";
+
+    area.set_text(text);
+    assert_eq!(area.text(), text);
+    assert_eq!(area.line_count(), 4);
+
+    area.set_text("price: 5€");
+    assert_eq!(area.text(), "price: 5?");
+}
+
+#[test]
+fn text_area_wraps_and_numbers_lines() {
+    let mut screen = TextBufferScreen::new(12, 3);
+    let mut area = TextArea::new((0, 0, 12, 3).into(), TextAreaSettings::new().wrap().line_numbers());
+
+    area.set_text("the quick brown fox\nend");
+    area.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "1 the quick\n  brown fox\n2 end\n");
+
+    area.set_focused(true);
+    press(&mut area, Key::Down, KeyModifiers::NONE);
+    assert_eq!(area.cursor(), (0, 10));
+    press(&mut area, Key::Down, KeyModifiers::NONE);
+    press(&mut area, Key::Down, KeyModifiers::NONE);
+    press(&mut area, Key::End, KeyModifiers::NONE);
+    area.refresh(&mut screen);
+    assert_eq!(area.cursor(), (1, 3));
+    assert_eq!(area.view(), (0, 0));
+
+    area.insert_str("\nmore");
+    area.refresh(&mut screen);
+    assert_eq!(area.view(), (1, 0));
+    assert_eq!(text_snapshot(&screen), "  brown fox\n2 end\n3 more\n");
+}
+
+#[test]
+fn text_area_scrolls_horizontally_to_the_cursor() {
+    let mut screen = TextBufferScreen::new(5, 1);
+    let mut area = TextArea::new((0, 0, 5, 1).into(), TextAreaSettings::new());
+
+    area.insert_str("abcdefgh");
+    area.refresh(&mut screen);
+    assert_eq!(area.view(), (0, 4));
+    assert_eq!(text_snapshot(&screen), "efgh\n");
+
+    press(&mut area, Key::Home, KeyModifiers::NONE);
+    area.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "abcde\n");
+}
+
+#[test]
+fn textbox_wraps_words_with_hanging_indent() {
+    let mut screen = TextBufferScreen::new(10, 4);
+    let mut log = TextBox::new((0, 0, 10, 4).into());
+
+    log.place_string("You hit the orc for 5\nOk");
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "You hit\nthe orc\nfor 5\nOk\n");
+
+    log.set_hanging_indent(2);
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "You hit\n  the orc\n  for 5\nOk\n");
+
+    // Words longer than a row are split
+    log.place_string("\nabcdefghijklmn");
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "  for 5\nOk\nabcdefghij\n  klmn\n");
+}
+
+#[test]
+fn textbox_reflows_on_resize() {
+    let mut screen = TextBufferScreen::new(20, 3);
+    let mut log = TextBox::new((0, 0, 8, 3).into());
+
+    log.place_string("one two three");
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "\none two\nthree\n");
+
+    log.set_rect((0, 0, 20, 3).into());
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "\n\none two three\n");
+    assert_eq!(log.lines(), vec!["one two three"]);
+}
+
+#[test]
+fn textbox_scrollback_pages_and_snaps() {
+    let mut screen = TextBufferScreen::new(10, 3);
+    let mut log = TextBox::new((0, 0, 10, 3).into());
+    log.set_scrollback(5);
+
+    log.place_string("l1\nl2\nl3\nl4\nl5\nl6");
+    assert_eq!(log.lines(), vec!["l2", "l3", "l4", "l5", "l6"]);
+
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "l4\nl5\nl6\n");
+
+    press(&mut log, Key::PageUp, KeyModifiers::NONE);
+    assert_eq!(log.scroll_offset(), 2);
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "l2\nl3\nl4  more ▼\n");
+
+    // While scrolled up new text does not move the view
+    log.place_string("\nl7");
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "l3\nl4\nl5  more ▼\n");
+
+    log.on_event(&InputEvent::mouse(MouseEventKind::Wheel { x: 0, y: -1 }, (1, 1).into()));
+    assert!(log.is_at_bottom());
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "l5\nl6\nl7\n");
+
+    // At the bottom new text keeps the view following it
+    log.place_string("\nl8");
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "l6\nl7\nl8\n");
+}
+
+#[test]
+fn textbox_scrolling_up_a_short_log_keeps_following() {
+    let mut screen = TextBufferScreen::new(10, 3);
+    let mut log = TextBox::new((0, 0, 10, 3).into());
+
+    log.place_string("l1\nl2");
+    press(&mut log, Key::PageUp, KeyModifiers::NONE);
+    log.on_event(&InputEvent::mouse(MouseEventKind::Wheel { x: 0, y: 1 }, (1, 1).into()));
+    assert!(log.is_at_bottom());
+
+    log.place_string("\nl3\nl4\nl5");
+    assert!(log.is_at_bottom());
+    log.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "l3\nl4\nl5\n");
+}
+
+fn numbered_scrollbox(rect: (isize, isize, usize, usize), size: (usize, usize)) -> ScrollBox {
+    let mut scrollbox = ScrollBox::new(rect.into(), size);
+    for y in 0..size.1 {
+        let line: String = (0..size.0).map(|x| char::from(b'a' + ((x + y) % 26) as u8)).collect();
+        scrollbox.write_string((0, y as isize).into(), &line, CharacterColor::White.into()).unwrap();
+    }
+    scrollbox
+}
+
+#[test]
+fn scrollbox_bounded_scrolling() {
+    let mut scrollbox = numbered_scrollbox((0, 0, 4, 3), (10, 8));
+    scrollbox.set_bounded(true);
+
+    scrollbox.scroll_to(-5, 100);
+    assert_eq!(scrollbox.scroll(), (0, 5));
+    // Horizontal scrolling shifts the content, so a negative amount moves the view right
+    scrollbox.scroll_horizontal(-100);
+    assert_eq!(scrollbox.scroll(), (6, 5));
+    scrollbox.scroll_horizontal(2);
+    assert_eq!(scrollbox.scroll(), (4, 5));
+    scrollbox.scroll_horizontal(-2);
+
+    scrollbox.scroll_into_view((1, 2, 2, 1).into());
+    assert_eq!(scrollbox.scroll(), (1, 2));
+
+    scrollbox.scroll_pages(-1);
+    assert_eq!(scrollbox.scroll(), (1, 0));
+    scrollbox.scroll_pages(1);
+    assert_eq!(scrollbox.scroll(), (1, 2));
+
+    let mut screen = TextBufferScreen::new(4, 3);
+    scrollbox.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "defg\nefgh\nfghi\n");
+
+    // Unbounded, a positive amount moves the content right of the viewport's left edge
+    let mut scrollbox = numbered_scrollbox((0, 0, 4, 3), (10, 8));
+    scrollbox.scroll_horizontal(2);
+    assert_eq!(scrollbox.scroll(), (-2, 0));
+    scrollbox.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "  ab\n  bc\n  cd\n");
+}
+
+#[test]
+fn scrollbox_draws_scrollbars() {
+    let mut screen = TextBufferScreen::new(6, 5);
+    let mut scrollbox = numbered_scrollbox((0, 0, 6, 5), (10, 8));
+    scrollbox.set_bounded(true);
+    scrollbox.set_scrollbars(true, true);
+    assert_eq!(scrollbox.viewport(), (0, 0, 5, 4).into());
+
+    scrollbox.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "abcde▲\nbcdef▓\ncdefg░\ndefgh▼\n◄▓░░►\n");
+
+    scrollbox.scroll_to(100, 100);
+    scrollbox.refresh(&mut screen);
+    assert_eq!(text_snapshot(&screen), "jklmn▲\nklmno░\nlmnop▓\nmnopq▼\n◄░░▓►\n");
+}
+
+#[test]
+fn scrollbox_scrollbar_clicks() {
+    let mut scrollbox = numbered_scrollbox((0, 0, 6, 5), (10, 20));
+    scrollbox.set_bounded(true);
+    scrollbox.set_scrollbars(true, true);
+
+    assert!(!scrollbox.click((2, 2).into()));
+    assert!(scrollbox.click((5, 3).into()));
+    assert_eq!(scrollbox.scroll(), (0, 1));
+    assert!(scrollbox.click((5, 2).into()));
+    assert_eq!(scrollbox.scroll(), (0, 4));
+    assert!(scrollbox.click((5, 0).into()));
+    assert_eq!(scrollbox.scroll(), (0, 3));
+    assert!(scrollbox.click((4, 4).into()));
+    assert_eq!(scrollbox.scroll(), (1, 3));
+}
+
+#[test]
+fn scrollbox_smooth_page_scrolling() {
+    let mut screen = TextBufferScreen::new(4, 5);
+    let mut scrollbox = numbered_scrollbox((0, 0, 4, 5), (4, 30));
+    scrollbox.set_smooth_scrolling(true);
+
+    assert!(scrollbox.on_event(&InputEvent::key(Key::PageDown, KeyModifiers::NONE)));
+    let mut steps = Vec::new();
+    while scrollbox.refresh(&mut screen) {
+        steps.push(scrollbox.scroll().1);
+    }
+
+    assert_eq!(steps, vec![2, 3, 4]);
+    assert!(!scrollbox.is_scrolling());
+}